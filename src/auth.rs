@@ -0,0 +1,96 @@
+use crate::request::PyRequest;
+use pyo3::prelude::*;
+use std::collections::HashSet;
+
+/// The identity resolved for a request by the registered authenticator: a
+/// user id plus the set of permission strings it holds. Returned by a
+/// callback registered with `Rupy.set_authenticator`.
+#[pyclass]
+#[derive(Clone)]
+pub struct Identity {
+    #[pyo3(get)]
+    pub user_id: String,
+    permissions: HashSet<String>,
+}
+
+#[pymethods]
+impl Identity {
+    #[new]
+    #[pyo3(signature = (user_id, permissions=Vec::new()))]
+    fn new(user_id: String, permissions: Vec<String>) -> Self {
+        Identity {
+            user_id,
+            permissions: permissions.into_iter().collect(),
+        }
+    }
+
+    /// Check whether this identity holds a given permission
+    fn has_permission(&self, permission: String) -> bool {
+        self.permissions.contains(&permission)
+    }
+
+    /// Get all permissions held by this identity
+    fn get_permissions(&self) -> Vec<String> {
+        self.permissions.iter().cloned().collect()
+    }
+}
+
+impl Identity {
+    /// Whether this identity holds every permission in `required`
+    fn has_all(&self, required: &[String]) -> bool {
+        required.iter().all(|p| self.permissions.contains(p))
+    }
+}
+
+/// Outcome of running the registered authenticator against a request.
+pub enum AuthOutcome {
+    /// No authenticator has been registered via `Rupy.set_authenticator`
+    NotConfigured,
+    Allowed(Identity),
+    Rejected(String),
+}
+
+/// Run the registered authenticator (if any) against `request`.
+///
+/// The authenticator receives the `PyRequest` and must return an `Identity`
+/// or raise to reject the request; any raised exception is turned into a
+/// `Rejected` outcome carrying the exception's message.
+pub fn authenticate(
+    authenticator: &Option<Py<PyAny>>,
+    py: Python,
+    request: &PyRequest,
+) -> AuthOutcome {
+    let Some(callback) = authenticator else {
+        return AuthOutcome::NotConfigured;
+    };
+
+    match callback.call1(py, (request.clone(),)) {
+        Ok(result) => match result.extract::<Identity>(py) {
+            Ok(identity) => AuthOutcome::Allowed(identity),
+            Err(_) => AuthOutcome::Rejected(
+                "Authenticator must return an Identity".to_string(),
+            ),
+        },
+        Err(e) => AuthOutcome::Rejected(e.to_string()),
+    }
+}
+
+/// Check that `identity` holds every permission in `required`.
+///
+/// Returns `Err` with a message describing the missing permission(s) if not;
+/// an empty `required` list always passes.
+pub fn check_permissions(identity: &Identity, required: &[String]) -> Result<(), String> {
+    if identity.has_all(required) {
+        Ok(())
+    } else {
+        let missing: Vec<&str> = required
+            .iter()
+            .filter(|p| !identity.permissions.contains(*p))
+            .map(|p| p.as_str())
+            .collect();
+        Err(format!(
+            "Missing required permission(s): {}",
+            missing.join(", ")
+        ))
+    }
+}
@@ -0,0 +1,237 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::{Mutex, OnceLock};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// App-wide signing key configured once via `Rupy.set_secret_key`.
+/// Stored globally (rather than threaded through every `PyRequest`/
+/// `PyResponse`) since handlers construct those directly without a
+/// reference back to the app.
+static SECRET_KEY: OnceLock<Mutex<Vec<u8>>> = OnceLock::new();
+
+/// Set the secret key used to sign and verify cookies
+pub fn set_secret_key(key: Vec<u8>) {
+    let lock = SECRET_KEY.get_or_init(|| Mutex::new(Vec::new()));
+    *lock.lock().unwrap() = key;
+}
+
+fn secret_key() -> Vec<u8> {
+    SECRET_KEY
+        .get()
+        .map(|lock| lock.lock().unwrap().clone())
+        .unwrap_or_default()
+}
+
+/// Sign `name=value` with the configured secret key, returning
+/// `base64(HMAC-SHA256) || "." || value` for storage as a cookie value.
+pub fn sign(name: &str, value: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(&secret_key()).expect("HMAC accepts a key of any size");
+    mac.update(name.as_bytes());
+    mac.update(b"=");
+    mac.update(value.as_bytes());
+    let signature = mac.finalize().into_bytes();
+    format!("{}.{}", BASE64.encode(signature), value)
+}
+
+/// Verify a `sign`-produced cookie value, returning the original value only
+/// if the signature matches. The signature comparison is constant-time
+/// (via `Mac::verify_slice`), so a missing or tampered signature behaves
+/// exactly like an absent cookie rather than leaking timing information.
+pub fn verify(name: &str, signed_value: &str) -> Option<String> {
+    let (signature_b64, value) = signed_value.split_once('.')?;
+    let expected_signature = BASE64.decode(signature_b64).ok()?;
+
+    let mut mac = HmacSha256::new_from_slice(&secret_key()).ok()?;
+    mac.update(name.as_bytes());
+    mac.update(b"=");
+    mac.update(value.as_bytes());
+    mac.verify_slice(&expected_signature).ok()?;
+
+    Some(value.to_string())
+}
+
+// Build a Set-Cookie header value from a cookie's name/value and attributes.
+// Shared by `PyResponse::set_cookie`/`set_signed_cookie` and `Cookie::to_header`,
+// which only differ in where the attributes come from.
+#[allow(clippy::too_many_arguments)]
+pub fn build_set_cookie_header(
+    name: &str,
+    value: &str,
+    max_age: Option<i64>,
+    expires: Option<String>,
+    path: Option<String>,
+    domain: Option<String>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<String>,
+) -> String {
+    let mut header = format!("{}={}", name, value);
+
+    if let Some(age) = max_age {
+        header.push_str(&format!("; Max-Age={}", age));
+    }
+
+    if let Some(exp) = expires {
+        header.push_str(&format!("; Expires={}", exp));
+    }
+
+    header.push_str(&format!(
+        "; Path={}",
+        path.unwrap_or_else(|| "/".to_string())
+    ));
+
+    if let Some(d) = domain {
+        header.push_str(&format!("; Domain={}", d));
+    }
+
+    if secure {
+        header.push_str("; Secure");
+    }
+
+    if http_only {
+        header.push_str("; HttpOnly");
+    }
+
+    if let Some(ss) = same_site {
+        header.push_str(&format!("; SameSite={}", ss));
+    }
+
+    header
+}
+
+/// A structured `Set-Cookie`, carrying attributes (path, domain,
+/// max-age/expires, secure, http-only, same-site) as first-class fields
+/// instead of a bare `name=value` pair.
+#[pyclass]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Cookie {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub value: String,
+    #[pyo3(get)]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub max_age: Option<i64>,
+    #[pyo3(get)]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub expires: Option<String>,
+    #[pyo3(get)]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub path: Option<String>,
+    #[pyo3(get)]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub domain: Option<String>,
+    #[pyo3(get)]
+    #[serde(skip_serializing_if = "is_false", default)]
+    pub secure: bool,
+    #[pyo3(get)]
+    #[serde(skip_serializing_if = "is_false", default)]
+    pub http_only: bool,
+    #[pyo3(get)]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub same_site: Option<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[pymethods]
+impl Cookie {
+    #[new]
+    #[pyo3(signature = (name, value, max_age=None, expires=None, path=None, domain=None, secure=false, http_only=false, same_site=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        name: String,
+        value: String,
+        max_age: Option<i64>,
+        expires: Option<String>,
+        path: Option<String>,
+        domain: Option<String>,
+        secure: bool,
+        http_only: bool,
+        same_site: Option<String>,
+    ) -> Self {
+        Cookie {
+            name,
+            value,
+            max_age,
+            expires,
+            path,
+            domain,
+            secure,
+            http_only,
+            same_site,
+        }
+    }
+
+    /// Serialize this cookie into a `Set-Cookie` header value
+    fn to_header(&self) -> String {
+        build_set_cookie_header(
+            &self.name,
+            &self.value,
+            self.max_age,
+            self.expires.clone(),
+            self.path.clone(),
+            self.domain.clone(),
+            self.secure,
+            self.http_only,
+            self.same_site.clone(),
+        )
+    }
+
+    /// Parse a `Set-Cookie` header value back into a `Cookie`
+    ///
+    /// Returns `None` if `header` doesn't start with a `name=value` pair.
+    /// Unknown attributes are ignored, so this round-trips every attribute
+    /// `to_header` can produce.
+    #[staticmethod]
+    fn parse(header: String) -> Option<Cookie> {
+        parse_set_cookie_header(&header)
+    }
+}
+
+/// Parse a `Set-Cookie` header value into a `Cookie`, attribute names
+/// matched case-insensitively per RFC 6265.
+pub fn parse_set_cookie_header(header: &str) -> Option<Cookie> {
+    let mut segments = header.split(';').map(|s| s.trim());
+    let (name, value) = segments.next()?.split_once('=')?;
+
+    let mut cookie = Cookie {
+        name: name.to_string(),
+        value: value.to_string(),
+        max_age: None,
+        expires: None,
+        path: None,
+        domain: None,
+        secure: false,
+        http_only: false,
+        same_site: None,
+    };
+
+    for attr in segments {
+        let (attr_name, attr_value) = match attr.split_once('=') {
+            Some((n, v)) => (n.trim(), Some(v.trim())),
+            None => (attr, None),
+        };
+
+        match (attr_name.to_ascii_lowercase().as_str(), attr_value) {
+            ("max-age", Some(v)) => cookie.max_age = v.parse().ok(),
+            ("expires", Some(v)) => cookie.expires = Some(v.to_string()),
+            ("path", Some(v)) => cookie.path = Some(v.to_string()),
+            ("domain", Some(v)) => cookie.domain = Some(v.to_string()),
+            ("samesite", Some(v)) => cookie.same_site = Some(v.to_string()),
+            ("secure", None) => cookie.secure = true,
+            ("httponly", None) => cookie.http_only = true,
+            _ => {}
+        }
+    }
+
+    Some(cookie)
+}
@@ -0,0 +1,162 @@
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Where to source the key(s) used to verify a JWT's signature.
+enum KeySource {
+    /// A static shared secret, used with HMAC algorithms (HS256/HS384/HS512).
+    Secret(Vec<u8>),
+    /// A JWKS endpoint URL; RSA/EC/PS keys are fetched and cached by `kid`.
+    Jwks(String),
+}
+
+struct JwtSettings {
+    key_source: KeySource,
+    algorithms: Vec<Algorithm>,
+    audience: Option<String>,
+    issuer: Option<String>,
+    leeway: u64,
+}
+
+/// App-wide JWT verification settings, configured once via
+/// `Rupy.configure_jwt`. Stored globally (like `cookie::SECRET_KEY`) since
+/// `PyRequest.jwt_claims` validates tokens without a reference back to the app.
+static JWT_SETTINGS: OnceLock<Mutex<Option<JwtSettings>>> = OnceLock::new();
+
+/// JWKS keys fetched from a `KeySource::Jwks`, cached by `kid` and refreshed
+/// in full whenever a token references a `kid` we haven't seen yet.
+static JWKS_CACHE: OnceLock<Mutex<HashMap<String, DecodingKey>>> = OnceLock::new();
+
+fn settings_lock() -> &'static Mutex<Option<JwtSettings>> {
+    JWT_SETTINGS.get_or_init(|| Mutex::new(None))
+}
+
+fn jwks_cache_lock() -> &'static Mutex<HashMap<String, DecodingKey>> {
+    JWKS_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Configure JWT Bearer-token verification.
+///
+/// `secret_or_jwks` is either a static shared secret (for `HS*` algorithms)
+/// or an `http(s)://` JWKS endpoint URL (for `RS*`/`ES*`/`PS*` algorithms).
+pub fn configure(
+    secret_or_jwks: String,
+    algorithms: Vec<String>,
+    audience: Option<String>,
+    issuer: Option<String>,
+    leeway: u64,
+) -> Result<(), String> {
+    let algorithms = algorithms
+        .iter()
+        .map(|a| parse_algorithm(a))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let key_source = if secret_or_jwks.starts_with("http://") || secret_or_jwks.starts_with("https://")
+    {
+        KeySource::Jwks(secret_or_jwks)
+    } else {
+        KeySource::Secret(secret_or_jwks.into_bytes())
+    };
+
+    *settings_lock().lock().unwrap() = Some(JwtSettings {
+        key_source,
+        algorithms,
+        audience,
+        issuer,
+        leeway,
+    });
+    // The new source's keys may not agree with whatever was cached for the old one.
+    jwks_cache_lock().lock().unwrap().clear();
+    Ok(())
+}
+
+/// Validate `token`'s signature and standard claims (`exp`/`nbf`/`aud`/`iss`)
+/// against the configured settings, returning the decoded claims as JSON.
+pub fn validate(token: &str) -> Result<Value, String> {
+    let guard = settings_lock().lock().unwrap();
+    let settings = guard
+        .as_ref()
+        .ok_or("JWT verification is not configured; call Rupy.configure_jwt first")?;
+
+    let header = decode_header(token).map_err(|e| format!("Invalid JWT header: {}", e))?;
+    if !settings.algorithms.contains(&header.alg) {
+        return Err(format!("Algorithm {:?} is not permitted", header.alg));
+    }
+
+    let decoding_key = match &settings.key_source {
+        KeySource::Secret(secret) => DecodingKey::from_secret(secret),
+        KeySource::Jwks(url) => {
+            let kid = header.kid.clone().ok_or_else(|| {
+                "JWT is missing a 'kid' header required for JWKS verification".to_string()
+            })?;
+            decoding_key_for_kid(url, &kid)?
+        }
+    };
+
+    let mut validation = Validation::new(header.alg);
+    validation.leeway = settings.leeway;
+    validation.validate_nbf = true;
+    if let Some(aud) = &settings.audience {
+        validation.set_audience(&[aud]);
+    } else {
+        validation.validate_aud = false;
+    }
+    if let Some(iss) = &settings.issuer {
+        validation.set_issuer(&[iss]);
+    }
+
+    decode::<Value>(token, &decoding_key, &validation)
+        .map(|data| data.claims)
+        .map_err(|e| format!("JWT validation failed: {}", e))
+}
+
+/// Look up `kid` in the JWKS cache, fetching (and fully replacing) the
+/// cached key set from `jwks_url` when it isn't found.
+fn decoding_key_for_kid(jwks_url: &str, kid: &str) -> Result<DecodingKey, String> {
+    {
+        let cache = jwks_cache_lock().lock().unwrap();
+        if let Some(key) = cache.get(kid) {
+            return Ok(key.clone());
+        }
+    }
+
+    let jwk_set: JwkSet = ureq::get(jwks_url)
+        .call()
+        .map_err(|e| format!("Failed to fetch JWKS from '{}': {}", jwks_url, e))?
+        .into_json()
+        .map_err(|e| format!("Failed to parse JWKS from '{}': {}", jwks_url, e))?;
+
+    let mut cache = jwks_cache_lock().lock().unwrap();
+    cache.clear();
+    for jwk in &jwk_set.keys {
+        if let Some(jwk_kid) = &jwk.common.key_id {
+            if let Ok(key) = DecodingKey::from_jwk(jwk) {
+                cache.insert(jwk_kid.clone(), key);
+            }
+        }
+    }
+
+    cache
+        .get(kid)
+        .cloned()
+        .ok_or_else(|| format!("Unknown JWT key id '{}'", kid))
+}
+
+fn parse_algorithm(name: &str) -> Result<Algorithm, String> {
+    match name {
+        "HS256" => Ok(Algorithm::HS256),
+        "HS384" => Ok(Algorithm::HS384),
+        "HS512" => Ok(Algorithm::HS512),
+        "RS256" => Ok(Algorithm::RS256),
+        "RS384" => Ok(Algorithm::RS384),
+        "RS512" => Ok(Algorithm::RS512),
+        "ES256" => Ok(Algorithm::ES256),
+        "ES384" => Ok(Algorithm::ES384),
+        "PS256" => Ok(Algorithm::PS256),
+        "PS384" => Ok(Algorithm::PS384),
+        "PS512" => Ok(Algorithm::PS512),
+        other => Err(format!("Unsupported JWT algorithm '{}'", other)),
+    }
+}
@@ -5,111 +5,50 @@ use axum::{
     response::IntoResponse,
     Router,
 };
-use handlebars::Handlebars;
 use multer::Multipart;
-use opentelemetry::{global, KeyValue};
-use opentelemetry_sdk::{metrics::SdkMeterProvider, trace::SdkTracerProvider, Resource};
+use opentelemetry::{
+    global,
+    propagation::{Extractor, Injector, TextMapPropagator},
+    trace::{TraceContextExt, Tracer},
+    Context as OtelContext, KeyValue,
+};
+use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    metrics::{PeriodicReader, SdkMeterProvider},
+    propagation::TraceContextPropagator,
+    trace::SdkTracerProvider,
+    Resource,
+};
 use opentelemetry_semantic_conventions as semcov;
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyTuple};
 use pyo3::IntoPyObjectExt;
 use serde_json::json;
 use std::collections::HashMap;
-use std::io::Write;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::{Instant, SystemTime};
-use tempfile::NamedTempFile;
+use tower_http::compression::{predicate::Predicate, CompressionLayer};
 use tower_http::trace::TraceLayer;
 use tracing::{error, info, span, warn, Level};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-// Python Request wrapper
-#[pyclass]
-#[derive(Clone)]
-pub struct PyRequest {
-    #[pyo3(get)]
-    method: String,
-    #[pyo3(get)]
-    path: String,
-    #[pyo3(get)]
-    body: String,
-    headers: HashMap<String, String>,
-    cookies: HashMap<String, String>,
-}
-
-#[pymethods]
-impl PyRequest {
-    #[new]
-    fn new(method: String, path: String, body: String) -> Self {
-        PyRequest {
-            method,
-            path,
-            body,
-            headers: HashMap::new(),
-            cookies: HashMap::new(),
-        }
-    }
-
-    fn get_header(&self, _py: Python, key: String) -> PyResult<Option<String>> {
-        Ok(self.headers.get(&key).cloned())
-    }
-
-    fn set_header(&mut self, _py: Python, key: String, value: String) -> PyResult<()> {
-        self.headers.insert(key, value);
-        Ok(())
-    }
-
-    #[getter]
-    fn headers(&self, py: Python) -> PyResult<Py<PyDict>> {
-        let dict = PyDict::new(py);
-        for (key, value) in &self.headers {
-            dict.set_item(key, value)?;
-        }
-        Ok(dict.into())
-    }
-
-    /// Get a cookie value by name
-    fn get_cookie(&self, _py: Python, name: String) -> PyResult<Option<String>> {
-        Ok(self.cookies.get(&name).cloned())
-    }
-
-    /// Set a cookie value (for middleware/handler use)
-    fn set_cookie(&mut self, _py: Python, name: String, value: String) -> PyResult<()> {
-        self.cookies.insert(name, value);
-        Ok(())
-    }
-
-    /// Get all cookies as a dictionary
-    #[getter]
-    fn cookies(&self, py: Python) -> PyResult<Py<PyDict>> {
-        let dict = PyDict::new(py);
-        for (key, value) in &self.cookies {
-            dict.set_item(key, value)?;
-        }
-        Ok(dict.into())
-    }
-
-    /// Get the auth token from the Authorization header (Bearer token)
-    #[getter]
-    fn auth_token(&self, _py: Python) -> PyResult<Option<String>> {
-        if let Some(auth_header) = self.headers.get("authorization") {
-            if let Some(token) = auth_header.strip_prefix("Bearer ") {
-                return Ok(Some(token.to_string()));
-            }
-        }
-        Ok(None)
-    }
-
-    /// Set the auth token in the Authorization header (Bearer token)
-    #[setter(auth_token)]
-    fn set_auth_token(&mut self, _py: Python, token: String) -> PyResult<()> {
-        self.headers
-            .insert("authorization".to_string(), format!("Bearer {}", token));
-        Ok(())
-    }
-}
+mod auth;
+mod cookie;
+mod jwt;
+mod multipart;
+mod request;
+mod script;
+mod session;
+mod storage;
+mod template;
+mod validation;
+
+use request::PyRequest;
+use script::{RhaiHandler, RhaiRequest};
+use template::TemplateEngine;
+use validation::ParamSpec;
 
 // Python Response wrapper
 #[pyclass]
@@ -160,12 +99,13 @@ impl PyResponse {
     ///     name: Cookie name
     ///     value: Cookie value
     ///     max_age: Optional max age in seconds
+    ///     expires: Optional `Expires` attribute as an HTTP-date string
     ///     path: Optional cookie path (default: "/")
     ///     domain: Optional domain
     ///     secure: Whether cookie should only be sent over HTTPS
     ///     http_only: Whether cookie should be HTTP-only (not accessible via JavaScript)
     ///     same_site: SameSite attribute ("Strict", "Lax", or "None")
-    #[pyo3(signature = (name, value, max_age=None, path=None, domain=None, secure=false, http_only=false, same_site=None))]
+    #[pyo3(signature = (name, value, max_age=None, expires=None, path=None, domain=None, secure=false, http_only=false, same_site=None))]
     #[allow(clippy::too_many_arguments)]
     fn set_cookie(
         &mut self,
@@ -173,40 +113,67 @@ impl PyResponse {
         name: String,
         value: String,
         max_age: Option<i64>,
+        expires: Option<String>,
         path: Option<String>,
         domain: Option<String>,
         secure: bool,
         http_only: bool,
         same_site: Option<String>,
     ) -> PyResult<()> {
-        let mut cookie = format!("{}={}", name, value);
-
-        if let Some(age) = max_age {
-            cookie.push_str(&format!("; Max-Age={}", age));
-        }
-
-        cookie.push_str(&format!(
-            "; Path={}",
-            path.unwrap_or_else(|| "/".to_string())
+        self.cookies.push(cookie::build_set_cookie_header(
+            &name, &value, max_age, expires, path, domain, secure, http_only, same_site,
         ));
+        Ok(())
+    }
 
-        if let Some(d) = domain {
-            cookie.push_str(&format!("; Domain={}", d));
-        }
-
-        if secure {
-            cookie.push_str("; Secure");
-        }
-
-        if http_only {
-            cookie.push_str("; HttpOnly");
-        }
-
-        if let Some(ss) = same_site {
-            cookie.push_str(&format!("; SameSite={}", ss));
-        }
+    /// Set a tamper-proof cookie, signed with the app's secret key
+    ///
+    /// Stores `value` alongside an HMAC-SHA256 signature computed over the
+    /// cookie name and value, so the client cannot forge or modify it
+    /// without invalidating the signature. Configure the signing key once
+    /// with `Rupy.set_secret_key(...)`; read it back with
+    /// `PyRequest.get_signed_cookie(name)`.
+    ///
+    /// Args: same as `set_cookie`.
+    #[pyo3(signature = (name, value, max_age=None, expires=None, path=None, domain=None, secure=false, http_only=false, same_site=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn set_signed_cookie(
+        &mut self,
+        _py: Python,
+        name: String,
+        value: String,
+        max_age: Option<i64>,
+        expires: Option<String>,
+        path: Option<String>,
+        domain: Option<String>,
+        secure: bool,
+        http_only: bool,
+        same_site: Option<String>,
+    ) -> PyResult<()> {
+        let signed_value = cookie::sign(&name, &value);
+        self.cookies.push(cookie::build_set_cookie_header(
+            &name,
+            &signed_value,
+            max_age,
+            expires,
+            path,
+            domain,
+            secure,
+            http_only,
+            same_site,
+        ));
+        Ok(())
+    }
 
-        self.cookies.push(cookie);
+    /// Set a cookie from a structured `Cookie` object
+    ///
+    /// Equivalent to `set_cookie`/`set_signed_cookie` but takes a `Cookie`
+    /// carrying its attributes (path, domain, max-age/expires, secure,
+    /// http-only, same-site) together, which round-trips through
+    /// `Cookie.parse` for code that needs to inspect or forward a cookie
+    /// a handler already built (e.g. the session subsystem).
+    fn set_cookie_obj(&mut self, _py: Python, cookie: cookie::Cookie) -> PyResult<()> {
+        self.cookies.push(cookie.to_header());
         Ok(())
     }
 
@@ -233,6 +200,69 @@ impl PyResponse {
         self.cookies.push(cookie);
         Ok(())
     }
+
+    /// Build a Server-Sent Events stream from `generator`
+    ///
+    /// Each item yielded by `generator` (a `bytes`/`str` chunk, anything else
+    /// via `str()`) is framed as `data: <item>\n\n`, with
+    /// `Content-Type: text/event-stream` and response buffering disabled so
+    /// events reach the client as soon as they're produced.
+    #[staticmethod]
+    #[pyo3(signature = (generator, status=200))]
+    fn event_stream(generator: Py<PyAny>, status: Option<u16>) -> PyStreamResponse {
+        let mut stream = PyStreamResponse::new(
+            generator,
+            status,
+            Some("text/event-stream".to_string()),
+        );
+        stream
+            .headers
+            .insert("cache-control".to_string(), "no-cache".to_string());
+        stream
+            .headers
+            .insert("x-accel-buffering".to_string(), "no".to_string());
+        stream.is_sse = true;
+        stream
+    }
+}
+
+// Python streaming response wrapper: body chunks are pulled from a Python
+// generator/iterator as they're produced, rather than buffered up front like
+// `PyResponse`.
+#[pyclass]
+#[derive(Clone)]
+pub struct PyStreamResponse {
+    generator: Py<PyAny>,
+    #[pyo3(get)]
+    status: u16,
+    headers: HashMap<String, String>,
+    is_sse: bool,
+}
+
+#[pymethods]
+impl PyStreamResponse {
+    /// Create a streaming response whose body is produced by iterating
+    /// `generator`, which must yield `bytes` or `str` chunks.
+    #[new]
+    #[pyo3(signature = (generator, status=200, content_type=None))]
+    fn new(generator: Py<PyAny>, status: Option<u16>, content_type: Option<String>) -> Self {
+        let mut headers = HashMap::new();
+        headers.insert(
+            "content-type".to_string(),
+            content_type.unwrap_or_else(|| "application/octet-stream".to_string()),
+        );
+        PyStreamResponse {
+            generator,
+            status: status.unwrap_or(200),
+            headers,
+            is_sse: false,
+        }
+    }
+
+    fn set_header(&mut self, key: String, value: String) -> PyResult<()> {
+        self.headers.insert(key, value);
+        Ok(())
+    }
 }
 
 // Python UploadFile wrapper
@@ -247,17 +277,47 @@ pub struct PyUploadFile {
     size: u64,
     #[pyo3(get)]
     path: String,
+    #[pyo3(get)]
+    content: Vec<u8>,
+}
+
+impl PyUploadFile {
+    /// Build an upload file whose bytes live on disk (`path` set) or in
+    /// memory (`content` set) — exactly one of the two is populated.
+    pub(crate) fn from_parts(
+        filename: String,
+        content_type: String,
+        size: u64,
+        path: String,
+        content: Vec<u8>,
+    ) -> Self {
+        PyUploadFile {
+            filename,
+            content_type,
+            size,
+            path,
+            content,
+        }
+    }
 }
 
 #[pymethods]
 impl PyUploadFile {
     #[new]
-    fn new(filename: String, content_type: String, size: u64, path: String) -> Self {
+    #[pyo3(signature = (filename, content_type, size, path, content=Vec::new()))]
+    fn new(
+        filename: String,
+        content_type: String,
+        size: u64,
+        path: String,
+        content: Vec<u8>,
+    ) -> Self {
         PyUploadFile {
             filename,
             content_type,
             size,
             path,
+            content,
         }
     }
 
@@ -276,10 +336,15 @@ impl PyUploadFile {
         Ok(self.size)
     }
 
-    /// Get the temporary file path where the file is stored
+    /// Get the temporary file path where the file is stored (empty if held in memory)
     fn get_path(&self) -> PyResult<String> {
         Ok(self.path.clone())
     }
+
+    /// Get the in-memory file contents (empty if spooled to disk, see `path`)
+    fn get_content(&self) -> PyResult<Vec<u8>> {
+        Ok(self.content.clone())
+    }
 }
 
 // Upload configuration
@@ -287,7 +352,7 @@ impl PyUploadFile {
 struct UploadConfig {
     accepted_mime_types: Vec<String>,
     max_size: Option<u64>,
-    upload_dir: String,
+    backend: storage::UploadBackend,
 }
 
 // Route information
@@ -301,6 +366,14 @@ struct RouteInfo {
     content_type: String,     // Content type for the response
     is_upload: bool,          // Whether this route handles file uploads
     upload_config: Option<UploadConfig>, // Upload configuration
+    permissions: Vec<String>, // Permissions required by `Rupy.set_authenticator`'s identity
+    params: Vec<ParamSpec>, // Declared parameter schema, validated before the handler runs
+    is_static: bool,          // Whether this route serves files from `static_dir`
+    static_dir: Option<String>, // Directory served by a static route
+    timeout_ms: Option<u64>, // Per-route override for the app-wide request timeout
+    summary: Option<String>, // Short human-readable description, surfaced in the OpenAPI document
+    response_example: Option<serde_json::Value>, // Example response body, surfaced in the OpenAPI document
+    rhai_handler: Option<RhaiHandler>, // Set by `route_script`; dispatched without the GIL when present
 }
 
 impl Clone for RouteInfo {
@@ -315,6 +388,14 @@ impl Clone for RouteInfo {
             content_type: self.content_type.clone(),
             is_upload: self.is_upload,
             upload_config: self.upload_config.clone(),
+            permissions: self.permissions.clone(),
+            params: self.params.clone(),
+            is_static: self.is_static,
+            static_dir: self.static_dir.clone(),
+            timeout_ms: self.timeout_ms,
+            summary: self.summary.clone(),
+            response_example: self.response_example.clone(),
+            rhai_handler: self.rhai_handler.clone(),
         })
     }
 }
@@ -347,14 +428,64 @@ struct TemplateConfig {
     template_dirs: Vec<String>,
 }
 
+/// Static file mounts registered via `Rupy.mount_static`, checked against
+/// the request path before route matching. `(prefix, directory)` pairs;
+/// the longest matching prefix wins when mounts overlap.
+#[derive(Clone, Default)]
+struct StaticMountConfig {
+    mounts: Vec<(String, String)>,
+}
+
+/// Controls the auto-generated OpenAPI 3.0 document served by
+/// `Rupy.enable_openapi`.
+#[derive(Clone)]
+struct OpenApiConfig {
+    enabled: bool,
+    path: String,
+    title: String,
+    version: String,
+}
+
+// Response compression configuration
+#[derive(Clone)]
+struct CompressionConfig {
+    enabled: bool,
+    min_size: u32,
+    /// Content-Type prefixes eligible for compression; empty means "all types".
+    content_types: Vec<String>,
+}
+
+// Cross-Origin Resource Sharing configuration
+#[derive(Clone)]
+struct CorsConfig {
+    enabled: bool,
+    /// "*" means any origin is allowed; otherwise only a listed origin is
+    /// ever reflected back, never the whole list.
+    allowed_origins: Vec<String>,
+    allowed_methods: Vec<String>,
+    /// "*" means any requested header is echoed back on preflight.
+    allowed_headers: Vec<String>,
+    exposed_headers: Vec<String>,
+    allow_credentials: bool,
+    max_age: Option<u64>,
+}
+
 #[pyclass]
 struct Rupy {
     host: String,
     port: u16,
     routes: Arc<Mutex<Vec<RouteInfo>>>,
     middlewares: Arc<Mutex<Vec<MiddlewareInfo>>>,
+    error_handlers: Arc<Mutex<HashMap<u16, Py<PyAny>>>>,
     telemetry_config: Arc<Mutex<TelemetryConfig>>,
     template_config: Arc<Mutex<TemplateConfig>>,
+    template_engine: Arc<TemplateEngine>,
+    compression_config: Arc<Mutex<CompressionConfig>>,
+    authenticator: Arc<Mutex<Option<Py<PyAny>>>>,
+    default_timeout_ms: Arc<Mutex<Option<u64>>>,
+    cors_config: Arc<Mutex<CorsConfig>>,
+    static_mounts: Arc<Mutex<StaticMountConfig>>,
+    openapi_config: Arc<Mutex<OpenApiConfig>>,
 }
 
 #[pymethods]
@@ -374,6 +505,7 @@ impl Rupy {
             port: 8000,
             routes: Arc::new(Mutex::new(Vec::new())),
             middlewares: Arc::new(Mutex::new(Vec::new())),
+            error_handlers: Arc::new(Mutex::new(HashMap::new())),
             telemetry_config: Arc::new(Mutex::new(TelemetryConfig {
                 enabled,
                 endpoint,
@@ -383,13 +515,64 @@ impl Rupy {
                 template_dir: "./template".to_string(),
                 template_dirs: vec!["./template".to_string()],
             })),
+            template_engine: Arc::new(TemplateEngine::new()),
+            compression_config: Arc::new(Mutex::new(CompressionConfig {
+                enabled: false,
+                min_size: 1024,
+                content_types: Vec::new(),
+            })),
+            authenticator: Arc::new(Mutex::new(None)),
+            default_timeout_ms: Arc::new(Mutex::new(None)),
+            cors_config: Arc::new(Mutex::new(CorsConfig {
+                enabled: false,
+                allowed_origins: vec!["*".to_string()],
+                allowed_methods: vec![
+                    "GET".to_string(),
+                    "POST".to_string(),
+                    "PUT".to_string(),
+                    "PATCH".to_string(),
+                    "DELETE".to_string(),
+                    "OPTIONS".to_string(),
+                ],
+                allowed_headers: Vec::new(),
+                exposed_headers: Vec::new(),
+                allow_credentials: false,
+                max_age: None,
+            })),
+            static_mounts: Arc::new(Mutex::new(StaticMountConfig::default())),
+            openapi_config: Arc::new(Mutex::new(OpenApiConfig {
+                enabled: false,
+                path: "/openapi.json".to_string(),
+                title: "rupy".to_string(),
+                version: "0.1.0".to_string(),
+            })),
         }
     }
 
-    fn route(&self, path: String, handler: Py<PyAny>, methods: Vec<String>) -> PyResult<()> {
+    #[pyo3(signature = (
+        path, handler, methods, permissions=None, params=None, timeout_ms=None,
+        summary=None, response_example=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn route(
+        &self,
+        py: Python,
+        path: String,
+        handler: Py<PyAny>,
+        methods: Vec<String>,
+        permissions: Option<Vec<String>>,
+        params: Option<Vec<ParamSpec>>,
+        timeout_ms: Option<u64>,
+        summary: Option<String>,
+        response_example: Option<Py<PyAny>>,
+    ) -> PyResult<()> {
         // Parse path parameters from the route pattern
         // e.g., "/user/<username>" -> path_params = ["username"]
         let path_params = parse_path_params(&path);
+        let response_example = match response_example {
+            Some(obj) => Some(template::py_any_to_json(obj.bind(py))?),
+            None => None,
+        };
 
         let route_info = RouteInfo {
             path,
@@ -401,6 +584,14 @@ impl Rupy {
             content_type: "text/html".to_string(),
             is_upload: false,
             upload_config: None,
+            permissions: permissions.unwrap_or_default(),
+            params: params.unwrap_or_default(),
+            is_static: false,
+            static_dir: None,
+            timeout_ms,
+            summary,
+            response_example,
+            rhai_handler: None,
         };
 
         let mut routes = self.routes.lock().unwrap();
@@ -418,7 +609,23 @@ impl Rupy {
         Ok(())
     }
 
+    /// Register a handler for unmatched routes (404) or handler failures
+    /// (500), replacing the built-in default for that status code.
+    ///
+    /// `handler` is called exactly like a route handler, with a `PyRequest`,
+    /// and may return a `PyResponse`, a dict (serialized as JSON), or a
+    /// plain string. If it raises instead, the built-in default response for
+    /// `status_code` is used.
+    fn error_handler(&self, status_code: u16, handler: Py<PyAny>) -> PyResult<()> {
+        let mut error_handlers = self.error_handlers.lock().unwrap();
+        error_handlers.insert(status_code, handler);
+
+        Ok(())
+    }
+
     /// Register a template route
+    #[pyo3(signature = (path, handler, methods, template_name, content_type, permissions=None, timeout_ms=None))]
+    #[allow(clippy::too_many_arguments)]
     fn route_template(
         &self,
         path: String,
@@ -426,6 +633,8 @@ impl Rupy {
         methods: Vec<String>,
         template_name: String,
         content_type: String,
+        permissions: Option<Vec<String>>,
+        timeout_ms: Option<u64>,
     ) -> PyResult<()> {
         let path_params = parse_path_params(&path);
 
@@ -439,6 +648,14 @@ impl Rupy {
             content_type,
             is_upload: false,
             upload_config: None,
+            permissions: permissions.unwrap_or_default(),
+            params: Vec::new(),
+            is_static: false,
+            static_dir: None,
+            timeout_ms,
+            summary: None,
+            response_example: None,
+            rhai_handler: None,
         };
 
         let mut routes = self.routes.lock().unwrap();
@@ -448,7 +665,19 @@ impl Rupy {
     }
 
     /// Register an upload route
-    #[pyo3(signature = (path, handler, methods, accepted_mime_types=None, max_size=None, upload_dir=None))]
+    ///
+    /// Files are stored locally under `upload_dir` (the default) unless
+    /// `object_store_bucket` is given, in which case they're streamed to
+    /// that S3-compatible bucket instead and `PyUploadFile.path` is set to
+    /// the resulting object URL rather than a filesystem path. Size-limit
+    /// and MIME-type checks behave identically across both backends.
+    #[pyo3(signature = (
+        path, handler, methods, accepted_mime_types=None, max_size=None, upload_dir=None,
+        permissions=None, object_store_endpoint=None, object_store_bucket=None,
+        object_store_region=None, object_store_access_key=None, object_store_secret_key=None,
+        object_store_path_style=true, timeout_ms=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
     fn route_upload(
         &self,
         path: String,
@@ -457,19 +686,47 @@ impl Rupy {
         accepted_mime_types: Option<Vec<String>>,
         max_size: Option<u64>,
         upload_dir: Option<String>,
+        permissions: Option<Vec<String>>,
+        object_store_endpoint: Option<String>,
+        object_store_bucket: Option<String>,
+        object_store_region: Option<String>,
+        object_store_access_key: Option<String>,
+        object_store_secret_key: Option<String>,
+        object_store_path_style: bool,
+        timeout_ms: Option<u64>,
     ) -> PyResult<()> {
         let path_params = parse_path_params(&path);
 
+        let backend = match object_store_bucket {
+            Some(bucket) => storage::UploadBackend::ObjectStore(storage::ObjectStoreConfig {
+                endpoint: object_store_endpoint.ok_or_else(|| {
+                    pyo3::exceptions::PyValueError::new_err("object_store_endpoint is required with object_store_bucket")
+                })?,
+                bucket,
+                region: object_store_region.unwrap_or_else(|| "us-east-1".to_string()),
+                access_key: object_store_access_key.ok_or_else(|| {
+                    pyo3::exceptions::PyValueError::new_err("object_store_access_key is required with object_store_bucket")
+                })?,
+                secret_key: object_store_secret_key.ok_or_else(|| {
+                    pyo3::exceptions::PyValueError::new_err("object_store_secret_key is required with object_store_bucket")
+                })?,
+                path_style: object_store_path_style,
+            }),
+            None => storage::UploadBackend::LocalFs {
+                upload_dir: upload_dir.unwrap_or_else(|| {
+                    // Use a more secure default than /tmp
+                    std::env::temp_dir()
+                        .join("rupy-uploads")
+                        .to_string_lossy()
+                        .to_string()
+                }),
+            },
+        };
+
         let upload_config = UploadConfig {
             accepted_mime_types: accepted_mime_types.unwrap_or_default(),
             max_size,
-            upload_dir: upload_dir.unwrap_or_else(|| {
-                // Use a more secure default than /tmp
-                std::env::temp_dir()
-                    .join("rupy-uploads")
-                    .to_string_lossy()
-                    .to_string()
-            }),
+            backend,
         };
 
         let route_info = RouteInfo {
@@ -482,6 +739,126 @@ impl Rupy {
             content_type: "application/json".to_string(),
             is_upload: true,
             upload_config: Some(upload_config),
+            permissions: permissions.unwrap_or_default(),
+            params: Vec::new(),
+            is_static: false,
+            static_dir: None,
+            timeout_ms,
+            summary: None,
+            response_example: None,
+            rhai_handler: None,
+        };
+
+        let mut routes = self.routes.lock().unwrap();
+        routes.push(route_info);
+
+        Ok(())
+    }
+
+    /// Register a static-file route serving files out of `directory`
+    ///
+    /// `path` must end with a catch-all parameter (e.g.
+    /// `/static/<filepath:path>`) whose matched value is joined onto `directory`
+    /// to locate the file on disk. Responses honor `Range: bytes=...`
+    /// requests (returning `206 Partial Content` or `416 Range Not
+    /// Satisfiable`) and always advertise `Accept-Ranges: bytes`.
+    #[pyo3(signature = (path, directory, permissions=None, timeout_ms=None))]
+    fn route_static(
+        &self,
+        path: String,
+        directory: String,
+        permissions: Option<Vec<String>>,
+        timeout_ms: Option<u64>,
+    ) -> PyResult<()> {
+        let path_params = parse_path_params(&path);
+
+        let route_info = RouteInfo {
+            path,
+            handler: Python::attach(|py| py.None()),
+            path_params,
+            methods: vec!["GET".to_string()],
+            is_template: false,
+            template_name: None,
+            content_type: "application/octet-stream".to_string(),
+            is_upload: false,
+            upload_config: None,
+            permissions: permissions.unwrap_or_default(),
+            params: Vec::new(),
+            is_static: true,
+            static_dir: Some(directory),
+            timeout_ms,
+            summary: None,
+            response_example: None,
+            rhai_handler: None,
+        };
+
+        let mut routes = self.routes.lock().unwrap();
+        routes.push(route_info);
+
+        Ok(())
+    }
+
+    /// Register a route handled by a Rhai script instead of a Python
+    /// callable, for simple endpoints (redirects, small transforms) that
+    /// don't need the GIL. Give either `script` (an inline script body) or
+    /// `script_path` (a `.rhai` file), not both; the script is compiled on
+    /// first request and the `AST` is cached for the route's lifetime.
+    ///
+    /// The script sees its request as a `request` variable (`method`,
+    /// `path`, `query`, `headers`, `body`) and its return value is handled
+    /// like a Python handler's: a string becomes the response body, an
+    /// object map is serialized as JSON (or rendered as a template if
+    /// `template_name` is given).
+    #[pyo3(signature = (
+        path, methods, script=None, script_path=None, template_name=None,
+        permissions=None, timeout_ms=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn route_script(
+        &self,
+        path: String,
+        methods: Vec<String>,
+        script: Option<String>,
+        script_path: Option<String>,
+        template_name: Option<String>,
+        permissions: Option<Vec<String>>,
+        timeout_ms: Option<u64>,
+    ) -> PyResult<()> {
+        let rhai_handler = match (script, script_path) {
+            (Some(script), None) => RhaiHandler::inline(script),
+            (None, Some(script_path)) => RhaiHandler::file(script_path),
+            (Some(_), Some(_)) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "route_script takes only one of 'script' or 'script_path'",
+                ))
+            }
+            (None, None) => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "route_script requires either 'script' or 'script_path'",
+                ))
+            }
+        };
+
+        let path_params = parse_path_params(&path);
+
+        let route_info = RouteInfo {
+            path,
+            handler: Python::attach(|py| py.None()),
+            path_params,
+            methods,
+            is_template: template_name.is_some(),
+            template_name,
+            content_type: "text/plain".to_string(),
+            is_upload: false,
+            upload_config: None,
+            permissions: permissions.unwrap_or_default(),
+            params: Vec::new(),
+            is_static: false,
+            static_dir: None,
+            timeout_ms,
+            summary: None,
+            response_example: None,
+            rhai_handler: Some(rhai_handler),
         };
 
         let mut routes = self.routes.lock().unwrap();
@@ -490,6 +867,47 @@ impl Rupy {
         Ok(())
     }
 
+    /// Mount `directory` for static file serving under `prefix`.
+    ///
+    /// Checked against every request path before route matching: if the
+    /// path starts with `prefix`, the remainder is joined onto `directory`,
+    /// canonicalized, and verified to still be inside `directory`'s
+    /// canonical form, rejecting any `../` escape with `403` rather than
+    /// falling through to the route table. The longest matching prefix wins
+    /// when mounts overlap.
+    fn mount_static(&self, prefix: String, directory: String) -> PyResult<()> {
+        let mut config = self.static_mounts.lock().unwrap();
+        config.mounts.push((prefix, directory));
+        Ok(())
+    }
+
+    /// Enable serving an auto-generated OpenAPI 3.0 document derived from
+    /// the currently registered routes, at `path` (default `/openapi.json`).
+    ///
+    /// The document is (re)built from the live route table on every request
+    /// to `path`, so routes registered after this call are still reflected.
+    #[pyo3(signature = (enabled=true, path=None, title=None, version=None))]
+    fn enable_openapi(
+        &self,
+        enabled: bool,
+        path: Option<String>,
+        title: Option<String>,
+        version: Option<String>,
+    ) -> PyResult<()> {
+        let mut config = self.openapi_config.lock().unwrap();
+        config.enabled = enabled;
+        if let Some(path) = path {
+            config.path = path;
+        }
+        if let Some(title) = title {
+            config.title = title;
+        }
+        if let Some(version) = version {
+            config.version = version;
+        }
+        Ok(())
+    }
+
     /// Set the template directory
     fn set_template_dir(&self, dir: String) -> PyResult<()> {
         let mut config = self.template_config.lock().unwrap();
@@ -539,14 +957,38 @@ impl Rupy {
             drop(config); // Release lock before rendering
 
             // Convert Python dict to JSON value
-            let json_context = py_dict_to_json(py, &context)?;
+            let json_context = template::py_dict_to_json(py, &context)?;
 
             // Try to render the template
-            render_template_with_dirs(&dirs, &template_name, &json_context)
+            self.template_engine
+                .render(&dirs, &template_name, &json_context)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e))
         })
     }
 
+    /// Register a Python callable as a Handlebars helper (e.g. `{{upper name}}`)
+    ///
+    /// The callable receives each helper argument converted from Handlebars'
+    /// JSON value to the equivalent Python object, and must return a string,
+    /// which is written verbatim into the rendered output.
+    fn register_template_helper(&self, name: String, callback: Py<PyAny>) -> PyResult<()> {
+        self.template_engine.register_helper(&name, callback);
+        Ok(())
+    }
+
+    /// Enable or disable template hot-reload.
+    ///
+    /// Off (the default) re-registers templates only when the configured
+    /// `template_dirs` list itself changes — zero disk I/O on every other
+    /// render. On, every render stats each `.hbs` file and re-registers only
+    /// the ones whose mtime moved, so edits take effect immediately; meant
+    /// for local development, not production, since it pays a `stat()` per
+    /// file per render.
+    fn set_template_dev_mode(&self, enabled: bool) -> PyResult<()> {
+        self.template_engine.set_dev_mode(enabled);
+        Ok(())
+    }
+
     /// Enable OpenTelemetry tracing, metrics, and logging
     #[pyo3(signature = (endpoint=None, service_name=None))]
     fn enable_telemetry(
@@ -594,17 +1036,152 @@ impl Rupy {
         Ok(())
     }
 
-    #[pyo3(signature = (host=None, port=None))]
-    fn run(&self, py: Python, host: Option<String>, port: Option<u16>) -> PyResult<()> {
-        let host = host.unwrap_or_else(|| self.host.clone());
-        let port = port.unwrap_or(self.port);
-        let routes = self.routes.clone();
-        let middlewares = self.middlewares.clone();
-        let telemetry_config = self.telemetry_config.clone();
-        let template_config = self.template_config.clone();
+    /// Set the secret key used to sign and verify cookies
+    ///
+    /// Required before calling `PyResponse.set_signed_cookie` or
+    /// `PyRequest.get_signed_cookie`; without it, cookies would effectively
+    /// be signed with an empty key.
+    fn set_secret_key(&self, key: String) -> PyResult<()> {
+        cookie::set_secret_key(key.into_bytes());
+        Ok(())
+    }
 
-        // Release the GIL before running the async server
-        py.detach(|| {
+    /// Enable or disable transparent response compression
+    ///
+    /// When enabled, responses are compressed with gzip, deflate, or brotli
+    /// depending on the request's `Accept-Encoding` header. `min_size` sets
+    /// the smallest body (in bytes) worth compressing (default 1024); bodies
+    /// under it are left alone. `content_types` restricts compression to
+    /// Content-Types starting with one of the given prefixes (e.g.
+    /// `["text/", "application/json"]`); an empty list (the default) means
+    /// every content type is eligible.
+    #[pyo3(signature = (enabled=true, min_size=None, content_types=None))]
+    fn set_compression(
+        &self,
+        enabled: bool,
+        min_size: Option<u32>,
+        content_types: Option<Vec<String>>,
+    ) -> PyResult<()> {
+        let mut config = self.compression_config.lock().unwrap();
+        config.enabled = enabled;
+        if let Some(size) = min_size {
+            config.min_size = size;
+        }
+        if let Some(types) = content_types {
+            config.content_types = types;
+        }
+        Ok(())
+    }
+
+    /// Enable and configure built-in CORS handling.
+    ///
+    /// `allowed_origins` defaults to `["*"]`; when it lists specific
+    /// origins instead, only the single requesting `Origin` that matches is
+    /// ever reflected back in `Access-Control-Allow-Origin` (plus a
+    /// `Vary: Origin`), never the whole configured list. `OPTIONS` requests
+    /// carrying `Access-Control-Request-Method` are answered directly with a
+    /// `204` before any route is dispatched.
+    #[pyo3(signature = (
+        enabled=true, allowed_origins=None, allowed_methods=None, allowed_headers=None,
+        exposed_headers=None, allow_credentials=false, max_age=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn set_cors(
+        &self,
+        enabled: bool,
+        allowed_origins: Option<Vec<String>>,
+        allowed_methods: Option<Vec<String>>,
+        allowed_headers: Option<Vec<String>>,
+        exposed_headers: Option<Vec<String>>,
+        allow_credentials: bool,
+        max_age: Option<u64>,
+    ) -> PyResult<()> {
+        let mut config = self.cors_config.lock().unwrap();
+        config.enabled = enabled;
+        if let Some(origins) = allowed_origins {
+            config.allowed_origins = origins;
+        }
+        if let Some(methods) = allowed_methods {
+            config.allowed_methods = methods;
+        }
+        if let Some(headers) = allowed_headers {
+            config.allowed_headers = headers;
+        }
+        if let Some(headers) = exposed_headers {
+            config.exposed_headers = headers;
+        }
+        config.allow_credentials = allow_credentials;
+        config.max_age = max_age;
+        Ok(())
+    }
+
+    /// Configure built-in JWT Bearer-token verification.
+    ///
+    /// `secret_or_jwks` is either a static shared secret (used with `HS*`
+    /// algorithms) or an `http(s)://` JWKS endpoint URL (used with
+    /// `RS*`/`ES*`/`PS*` algorithms, whose keys are fetched and cached by
+    /// `kid`, refreshed on an unknown `kid`). Once configured,
+    /// `PyRequest.jwt_claims` validates the request's Bearer token's
+    /// signature and `exp`/`nbf`/`aud`/`iss` claims and returns the decoded
+    /// claims as a dict.
+    #[pyo3(signature = (secret_or_jwks, algorithms=None, audience=None, issuer=None, leeway=60))]
+    fn configure_jwt(
+        &self,
+        secret_or_jwks: String,
+        algorithms: Option<Vec<String>>,
+        audience: Option<String>,
+        issuer: Option<String>,
+        leeway: u64,
+    ) -> PyResult<()> {
+        let algorithms =
+            algorithms.unwrap_or_else(|| vec!["HS256".to_string(), "RS256".to_string()]);
+        jwt::configure(secret_or_jwks, algorithms, audience, issuer, leeway)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// Register the authenticator callback used to resolve an `Identity` for
+    /// each request.
+    ///
+    /// The callback receives the `PyRequest` and must return an `Identity`
+    /// or raise to reject the request. It only gates routes registered with
+    /// a non-empty `permissions` list; routes without declared permissions
+    /// are unaffected.
+    fn set_authenticator(&self, callback: Py<PyAny>) -> PyResult<()> {
+        *self.authenticator.lock().unwrap() = Some(callback);
+        Ok(())
+    }
+
+    /// Set the app-wide default request timeout, in milliseconds.
+    ///
+    /// A route registered with its own `timeout_ms` overrides this default.
+    /// When a handler exceeds its timeout, the in-flight call is not
+    /// actually cancelled (there's no safe way to preempt a running Python
+    /// call) — the client just receives `504 Gateway Timeout` immediately
+    /// while the handler keeps running in the background.
+    fn set_request_timeout(&self, timeout_ms: u64) -> PyResult<()> {
+        *self.default_timeout_ms.lock().unwrap() = Some(timeout_ms);
+        Ok(())
+    }
+
+    #[pyo3(signature = (host=None, port=None))]
+    fn run(&self, py: Python, host: Option<String>, port: Option<u16>) -> PyResult<()> {
+        let host = host.unwrap_or_else(|| self.host.clone());
+        let port = port.unwrap_or(self.port);
+        let routes = self.routes.clone();
+        let middlewares = self.middlewares.clone();
+        let error_handlers = self.error_handlers.clone();
+        let telemetry_config = self.telemetry_config.clone();
+        let template_config = self.template_config.clone();
+        let template_engine = self.template_engine.clone();
+        let compression_config = self.compression_config.clone();
+        let authenticator = self.authenticator.clone();
+        let default_timeout_ms = self.default_timeout_ms.clone();
+        let cors_config = self.cors_config.clone();
+        let static_mounts = self.static_mounts.clone();
+        let openapi_config = self.openapi_config.clone();
+
+        // Release the GIL before running the async server
+        py.detach(|| {
             // Run the async server in a blocking context
             let runtime = tokio::runtime::Runtime::new().unwrap();
             runtime.block_on(async {
@@ -613,8 +1190,16 @@ impl Rupy {
                     port,
                     routes,
                     middlewares,
+                    error_handlers,
                     telemetry_config,
                     template_config,
+                    template_engine,
+                    compression_config,
+                    authenticator,
+                    default_timeout_ms,
+                    cors_config,
+                    static_mounts,
+                    openapi_config,
                 )
                 .await
             });
@@ -622,12 +1207,87 @@ impl Rupy {
 
         Ok(())
     }
+
+    /// Run the server with native TLS termination, instead of requiring an
+    /// external reverse proxy.
+    ///
+    /// The TLS backend is selected at build time via Cargo features: rustls
+    /// (the default, backed by `ring` and the bundled webpki CA roots) or,
+    /// with the `tls-native-tls` feature enabled, OpenSSL via the platform's
+    /// native trust store — useful on platforms without a working
+    /// rustls/ring toolchain, or for deployments that already link OpenSSL
+    /// for other reasons.
+    ///
+    /// `cert_path`/`key_path` point to a PEM certificate chain and private
+    /// key; HTTP/2 is negotiated automatically via ALPN. When
+    /// `http_redirect_port` is given, a plain-HTTP listener is also started
+    /// on that port, 301-redirecting every request to the HTTPS `port`.
+    /// Raises if the certificate or key fail to load.
+    #[pyo3(signature = (cert_path, key_path, host=None, port=None, http_redirect_port=None))]
+    fn run_tls(
+        &self,
+        py: Python,
+        cert_path: String,
+        key_path: String,
+        host: Option<String>,
+        port: Option<u16>,
+        http_redirect_port: Option<u16>,
+    ) -> PyResult<()> {
+        let host = host.unwrap_or_else(|| self.host.clone());
+        let port = port.unwrap_or(self.port);
+        let routes = self.routes.clone();
+        let middlewares = self.middlewares.clone();
+        let error_handlers = self.error_handlers.clone();
+        let telemetry_config = self.telemetry_config.clone();
+        let template_config = self.template_config.clone();
+        let template_engine = self.template_engine.clone();
+        let compression_config = self.compression_config.clone();
+        let authenticator = self.authenticator.clone();
+        let default_timeout_ms = self.default_timeout_ms.clone();
+        let cors_config = self.cors_config.clone();
+        let static_mounts = self.static_mounts.clone();
+        let openapi_config = self.openapi_config.clone();
+
+        // Release the GIL before running the async server
+        let result = py.detach(|| {
+            let runtime = tokio::runtime::Runtime::new().unwrap();
+            runtime.block_on(async {
+                run_server_tls(
+                    &host,
+                    port,
+                    cert_path,
+                    key_path,
+                    http_redirect_port,
+                    routes,
+                    middlewares,
+                    error_handlers,
+                    telemetry_config,
+                    template_config,
+                    template_engine,
+                    compression_config,
+                    authenticator,
+                    default_timeout_ms,
+                    cors_config,
+                    static_mounts,
+                    openapi_config,
+                )
+                .await
+            })
+        });
+
+        result.map_err(pyo3::exceptions::PyRuntimeError::new_err)
+    }
 }
 
 // Initialize OpenTelemetry
 fn init_telemetry(config: &TelemetryConfig) -> TelemetryGuard {
     let service_name = config.service_name.clone();
 
+    // Install the W3C trace-context propagator so this process both reads
+    // `traceparent`/`tracestate` from incoming requests and writes them on
+    // outgoing responses, letting rupy participate in distributed traces.
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
     // Create resource with service name
     let resource = Resource::builder()
         .with_attribute(KeyValue::new(
@@ -636,15 +1296,53 @@ fn init_telemetry(config: &TelemetryConfig) -> TelemetryGuard {
         ))
         .build();
 
-    // Create basic tracer provider
-    let tracer_provider = SdkTracerProvider::builder()
-        .with_resource(resource.clone())
-        .build();
+    let mut tracer_provider_builder = SdkTracerProvider::builder().with_resource(resource.clone());
+    let mut meter_provider_builder = SdkMeterProvider::builder().with_resource(resource);
+
+    // When an endpoint is configured, export spans and metrics to it over
+    // OTLP instead of only registering the providers in-process.
+    if let Some(endpoint) = &config.endpoint {
+        match SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint.clone())
+            .build()
+        {
+            Ok(span_exporter) => {
+                tracer_provider_builder =
+                    tracer_provider_builder.with_batch_exporter(span_exporter);
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to build OTLP span exporter for endpoint '{}': {}",
+                    endpoint, e
+                );
+            }
+        }
+
+        match MetricExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint.clone())
+            .build()
+        {
+            Ok(metric_exporter) => {
+                meter_provider_builder =
+                    meter_provider_builder.with_reader(PeriodicReader::builder(metric_exporter).build());
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to build OTLP metric exporter for endpoint '{}': {}",
+                    endpoint, e
+                );
+            }
+        }
+    }
 
+    // Create the tracer provider
+    let tracer_provider = tracer_provider_builder.build();
     global::set_tracer_provider(tracer_provider.clone());
 
-    // Create basic meter provider
-    let meter_provider = SdkMeterProvider::builder().with_resource(resource).build();
+    // Create the meter provider
+    let meter_provider = meter_provider_builder.build();
     global::set_meter_provider(meter_provider);
 
     // Initialize tracing subscriber with basic layers (no OpenTelemetry layer for now to avoid version conflicts)
@@ -676,32 +1374,260 @@ impl Drop for TelemetryGuard {
     }
 }
 
-async fn run_server(
-    host: &str,
-    port: u16,
-    routes: Arc<Mutex<Vec<RouteInfo>>>,
-    middlewares: Arc<Mutex<Vec<MiddlewareInfo>>>,
-    telemetry_config: Arc<Mutex<TelemetryConfig>>,
-    template_config: Arc<Mutex<TemplateConfig>>,
+/// Gates `CompressionLayer` on the live `CompressionConfig`: disabled
+/// entirely, or restricted by minimum body size and/or an eligible
+/// Content-Type prefix list, all configurable at runtime via
+/// `Rupy.set_compression`.
+#[derive(Clone)]
+struct CompressionGate {
+    config: Arc<Mutex<CompressionConfig>>,
+}
+
+impl Predicate for CompressionGate {
+    fn should_compress<B>(&self, response: &axum::http::Response<B>) -> bool
+    where
+        B: http_body::Body,
+    {
+        let config = self.config.lock().unwrap();
+        if !config.enabled {
+            return false;
+        }
+
+        if let Some(size) = response.body().size_hint().exact() {
+            if (size as u32) < config.min_size {
+                return false;
+            }
+        }
+
+        if config.content_types.is_empty() {
+            return true;
+        }
+
+        let content_type = response
+            .headers()
+            .get(axum::http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+
+        config
+            .content_types
+            .iter()
+            .any(|prefix| content_type.starts_with(prefix.as_str()))
+    }
+}
+
+/// Resolve the `Access-Control-Allow-Origin` value for `origin` against
+/// `cors_config`, and whether a `Vary: Origin` header needs to go alongside
+/// it. Returns `None` when CORS is disabled or `origin` isn't allowed.
+///
+/// When multiple specific origins are configured, only the single matching
+/// requesting origin is ever reflected back — never the whole list — since
+/// echoing the full list would let a caching proxy serve one origin's
+/// response to another.
+fn negotiate_cors_origin(cors_config: &CorsConfig, origin: Option<&str>) -> Option<(String, bool)> {
+    if !cors_config.enabled {
+        return None;
+    }
+    let origin = origin?;
+
+    let wildcard = cors_config.allowed_origins.iter().any(|o| o == "*");
+    if wildcard && !cors_config.allow_credentials {
+        // `Access-Control-Allow-Origin: *` is forbidden alongside credentials
+        // by the fetch spec, so fall through to reflecting the origin below.
+        return Some(("*".to_string(), false));
+    }
+    if wildcard || cors_config.allowed_origins.iter().any(|o| o == origin) {
+        return Some((origin.to_string(), true));
+    }
+    None
+}
+
+/// Insert `Access-Control-Allow-Origin` / `-Credentials` / `-Expose-Headers`
+/// into `header_map` for a non-preflight response, if `origin` is allowed.
+fn apply_cors_headers(
+    header_map: &mut axum::http::HeaderMap,
+    cors_config: &CorsConfig,
+    origin: Option<&str>,
 ) {
-    // Prepare Python for freethreaded access
-    Python::initialize();
+    use axum::http::header::{HeaderName, HeaderValue, VARY};
 
-    // Initialize telemetry if enabled
-    let config = telemetry_config.lock().unwrap().clone();
-    let _telemetry_guard = if config.enabled {
-        Some(init_telemetry(&config))
-    } else {
-        None
+    let Some((allow_origin, vary)) = negotiate_cors_origin(cors_config, origin) else {
+        return;
     };
 
-    // Create a router that matches all routes
-    let app = Router::new()
+    if let Ok(value) = HeaderValue::from_str(&allow_origin) {
+        header_map.insert(HeaderName::from_static("access-control-allow-origin"), value);
+    }
+    if vary {
+        header_map.insert(VARY, HeaderValue::from_static("Origin"));
+    }
+    if cors_config.allow_credentials {
+        header_map.insert(
+            HeaderName::from_static("access-control-allow-credentials"),
+            HeaderValue::from_static("true"),
+        );
+    }
+    if !cors_config.exposed_headers.is_empty() {
+        if let Ok(value) = HeaderValue::from_str(&cors_config.exposed_headers.join(", ")) {
+            header_map.insert(
+                HeaderName::from_static("access-control-expose-headers"),
+                value,
+            );
+        }
+    }
+}
+
+/// Build the `204` response for a CORS preflight (an `OPTIONS` request
+/// carrying `Access-Control-Request-Method`): lists every allowed method when
+/// the requested one is among them, and echoes back the requested headers
+/// that are allowed.
+fn build_cors_preflight_response(
+    cors_config: &CorsConfig,
+    origin: Option<&str>,
+    requested_method: Option<&str>,
+    requested_headers: Option<&str>,
+) -> axum::response::Response {
+    use axum::http::header::{HeaderName, HeaderValue};
+    use axum::response::IntoResponse;
+
+    let mut header_map = axum::http::HeaderMap::new();
+    apply_cors_headers(&mut header_map, cors_config, origin);
+
+    if requested_method
+        .is_some_and(|m| cors_config.allowed_methods.iter().any(|allowed| allowed == m))
+    {
+        if let Ok(value) = HeaderValue::from_str(&cors_config.allowed_methods.join(", ")) {
+            header_map.insert(HeaderName::from_static("access-control-allow-methods"), value);
+        }
+    }
+
+    let allow_all_headers = cors_config.allowed_headers.iter().any(|h| h == "*");
+    if let Some(requested) = requested_headers {
+        let echoed = if allow_all_headers {
+            requested.to_string()
+        } else {
+            requested
+                .split(',')
+                .map(str::trim)
+                .filter(|h| cors_config.allowed_headers.iter().any(|a| a.eq_ignore_ascii_case(h)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        if !echoed.is_empty() {
+            if let Ok(value) = HeaderValue::from_str(&echoed) {
+                header_map.insert(HeaderName::from_static("access-control-allow-headers"), value);
+            }
+        }
+    }
+
+    if let Some(max_age) = cors_config.max_age {
+        if let Ok(value) = HeaderValue::from_str(&max_age.to_string()) {
+            header_map.insert(HeaderName::from_static("access-control-max-age"), value);
+        }
+    }
+
+    let mut response = (StatusCode::NO_CONTENT, "").into_response();
+    response.headers_mut().extend(header_map);
+    response
+}
+
+/// Invoke a user-registered `Rupy.error_handler` callback for a given
+/// status code, through the same PyO3 call path as a normal route handler.
+///
+/// `handler` may return a `PyResponse`, a dict (serialized as JSON), or a
+/// plain string; `status_code` is used as the response's status for the
+/// latter two. Returns `None` if the handler itself raises, so the caller
+/// can fall back to the built-in default.
+fn invoke_error_handler(
+    py: Python,
+    handler: &Py<PyAny>,
+    py_request: PyRequest,
+    status_code: u16,
+    trace_cx: &OtelContext,
+    cors_config: &CorsConfig,
+    origin: Option<&str>,
+) -> Option<(axum::response::Response, u16)> {
+    use axum::response::IntoResponse;
+
+    let result = match handler.call1(py, (py_request,)) {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Registered error handler for {} raised: {:?}", status_code, e);
+            return None;
+        }
+    };
+
+    if let Ok(py_response) = result.extract::<PyResponse>(py) {
+        let status_u16 = py_response.status;
+        return Some((
+            build_response(py_response, trace_cx, None, None, None, cors_config, origin),
+            status_u16,
+        ));
+    }
+
+    if let Ok(py_dict) = result.cast_bound::<PyDict>(py) {
+        let context = match template::py_any_to_json(py_dict.as_any()) {
+            Ok(context) => context,
+            Err(e) => {
+                error!("Error handler for {} returned an invalid dict: {:?}", status_code, e);
+                return None;
+            }
+        };
+        let status = StatusCode::from_u16(status_code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (
+            status,
+            [(axum::http::header::CONTENT_TYPE, "application/json")],
+            context.to_string(),
+        )
+            .into_response();
+        apply_cors_headers(response.headers_mut(), cors_config, origin);
+        return Some((response, status_code));
+    }
+
+    if let Ok(text) = result.extract::<String>(py) {
+        let status = StatusCode::from_u16(status_code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, text).into_response();
+        apply_cors_headers(response.headers_mut(), cors_config, origin);
+        return Some((response, status_code));
+    }
+
+    error!(
+        "Error handler for {} returned a value that isn't a PyResponse, dict, or string",
+        status_code
+    );
+    None
+}
+
+// Build the router shared by the plain and TLS listeners: same route
+// dispatch, tracing, and compression layers either way.
+#[allow(clippy::too_many_arguments)]
+fn build_router(
+    routes: Arc<Mutex<Vec<RouteInfo>>>,
+    middlewares: Arc<Mutex<Vec<MiddlewareInfo>>>,
+    error_handlers: Arc<Mutex<HashMap<u16, Py<PyAny>>>>,
+    telemetry_config: Arc<Mutex<TelemetryConfig>>,
+    template_config: Arc<Mutex<TemplateConfig>>,
+    template_engine: Arc<TemplateEngine>,
+    compression_config: Arc<Mutex<CompressionConfig>>,
+    authenticator: Arc<Mutex<Option<Py<PyAny>>>>,
+    default_timeout_ms: Arc<Mutex<Option<u64>>>,
+    cors_config: Arc<Mutex<CorsConfig>>,
+    static_mounts: Arc<Mutex<StaticMountConfig>>,
+    openapi_config: Arc<Mutex<OpenApiConfig>>,
+) -> Router {
+    Router::new()
         .fallback(move |method, uri, request| {
             let routes = routes.clone();
             let middlewares = middlewares.clone();
+            let error_handlers = error_handlers.clone();
             let telemetry_config = telemetry_config.clone();
             let template_config = template_config.clone();
+            let template_engine = template_engine.clone();
+            let authenticator = authenticator.clone();
+            let default_timeout_ms = default_timeout_ms.clone();
+            let cors_config = cors_config.clone();
+            let static_mounts = static_mounts.clone();
+            let openapi_config = openapi_config.clone();
             async move {
                 handler_request(
                     method,
@@ -709,13 +1635,70 @@ async fn run_server(
                     request,
                     routes,
                     middlewares,
+                    error_handlers,
                     telemetry_config,
                     template_config,
+                    template_engine,
+                    authenticator,
+                    default_timeout_ms,
+                    cors_config,
+                    static_mounts,
+                    openapi_config,
                 )
                 .await
             }
         })
-        .layer(TraceLayer::new_for_http());
+        .layer(TraceLayer::new_for_http())
+        .layer(
+            CompressionLayer::new().compress_when(CompressionGate {
+                config: compression_config,
+            }),
+        )
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_server(
+    host: &str,
+    port: u16,
+    routes: Arc<Mutex<Vec<RouteInfo>>>,
+    middlewares: Arc<Mutex<Vec<MiddlewareInfo>>>,
+    error_handlers: Arc<Mutex<HashMap<u16, Py<PyAny>>>>,
+    telemetry_config: Arc<Mutex<TelemetryConfig>>,
+    template_config: Arc<Mutex<TemplateConfig>>,
+    template_engine: Arc<TemplateEngine>,
+    compression_config: Arc<Mutex<CompressionConfig>>,
+    authenticator: Arc<Mutex<Option<Py<PyAny>>>>,
+    default_timeout_ms: Arc<Mutex<Option<u64>>>,
+    cors_config: Arc<Mutex<CorsConfig>>,
+    static_mounts: Arc<Mutex<StaticMountConfig>>,
+    openapi_config: Arc<Mutex<OpenApiConfig>>,
+) {
+    // Prepare Python for freethreaded access
+    Python::initialize();
+
+    // Initialize telemetry if enabled
+    let config = telemetry_config.lock().unwrap().clone();
+    let _telemetry_guard = if config.enabled {
+        Some(init_telemetry(&config))
+    } else {
+        None
+    };
+
+    // Create a router that matches all routes
+    let app = build_router(
+        routes,
+        middlewares,
+        error_handlers,
+        telemetry_config,
+        template_config,
+        template_engine,
+        compression_config,
+        authenticator,
+        default_timeout_ms,
+        cors_config,
+        static_mounts,
+        openapi_config,
+    );
 
     let addr = format!("{}:{}", host, port).parse::<SocketAddr>().unwrap();
 
@@ -736,6 +1719,145 @@ async fn run_server(
     // Telemetry shutdown is handled by the Drop implementation of TelemetryGuard
 }
 
+/// Serve the same router as `run_server`, but terminating TLS directly
+/// instead of binding a plain `TcpListener`. HTTP/2 is negotiated
+/// automatically via ALPN by `axum_server`'s rustls acceptor. When
+/// `http_redirect_port` is set, a second plain-HTTP listener is spawned that
+/// 301-redirects every request to the HTTPS port.
+#[allow(clippy::too_many_arguments)]
+async fn run_server_tls(
+    host: &str,
+    port: u16,
+    cert_path: String,
+    key_path: String,
+    http_redirect_port: Option<u16>,
+    routes: Arc<Mutex<Vec<RouteInfo>>>,
+    middlewares: Arc<Mutex<Vec<MiddlewareInfo>>>,
+    error_handlers: Arc<Mutex<HashMap<u16, Py<PyAny>>>>,
+    telemetry_config: Arc<Mutex<TelemetryConfig>>,
+    template_config: Arc<Mutex<TemplateConfig>>,
+    template_engine: Arc<TemplateEngine>,
+    compression_config: Arc<Mutex<CompressionConfig>>,
+    authenticator: Arc<Mutex<Option<Py<PyAny>>>>,
+    default_timeout_ms: Arc<Mutex<Option<u64>>>,
+    cors_config: Arc<Mutex<CorsConfig>>,
+    static_mounts: Arc<Mutex<StaticMountConfig>>,
+    openapi_config: Arc<Mutex<OpenApiConfig>>,
+) -> Result<(), String> {
+    // Prepare Python for freethreaded access
+    Python::initialize();
+
+    // Initialize telemetry if enabled
+    let config = telemetry_config.lock().unwrap().clone();
+    let _telemetry_guard = if config.enabled {
+        Some(init_telemetry(&config))
+    } else {
+        None
+    };
+
+    // TLS backend is a build-time choice (Cargo feature `tls-native-tls`),
+    // so deployers without a working rustls/ring toolchain for their target
+    // (or who already link OpenSSL for other reasons) can swap it out
+    // without any runtime configuration.
+    #[cfg(feature = "tls-native-tls")]
+    let tls_config = axum_server::tls_openssl::OpenSSLConfig::from_pem_file(&cert_path, &key_path)
+        .map_err(|e| {
+            format!(
+                "Failed to load TLS certificate '{}' / key '{}': {}",
+                cert_path, key_path, e
+            )
+        })?;
+    #[cfg(not(feature = "tls-native-tls"))]
+    let tls_config = axum_server::tls_rustls::RustlsConfig::from_pem_file(&cert_path, &key_path)
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to load TLS certificate '{}' / key '{}': {}",
+                cert_path, key_path, e
+            )
+        })?;
+
+    let app = build_router(
+        routes,
+        middlewares,
+        error_handlers,
+        telemetry_config,
+        template_config,
+        template_engine,
+        compression_config,
+        authenticator,
+        default_timeout_ms,
+        cors_config,
+        static_mounts,
+        openapi_config,
+    );
+
+    let addr = format!("{}:{}", host, port)
+        .parse::<SocketAddr>()
+        .map_err(|e| format!("Invalid host/port '{}:{}': {}", host, port, e))?;
+
+    if let Some(redirect_port) = http_redirect_port {
+        let redirect_addr = format!("{}:{}", host, redirect_port)
+            .parse::<SocketAddr>()
+            .map_err(|e| {
+                format!(
+                    "Invalid redirect host/port '{}:{}': {}",
+                    host, redirect_port, e
+                )
+            })?;
+        let https_port = port;
+        tokio::spawn(async move {
+            let redirect_app = Router::new().fallback(
+                move |uri: Uri, headers: axum::http::HeaderMap| async move {
+                    let host_header = headers
+                        .get(axum::http::header::HOST)
+                        .and_then(|v| v.to_str().ok())
+                        .map(|h| h.split(':').next().unwrap_or(h).to_string())
+                        .unwrap_or_else(|| "localhost".to_string());
+                    let path_and_query =
+                        uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+                    let location =
+                        format!("https://{}:{}{}", host_header, https_port, path_and_query);
+                    axum::response::Redirect::permanent(&location)
+                },
+            );
+
+            match tokio::net::TcpListener::bind(redirect_addr).await {
+                Ok(listener) => {
+                    if let Err(e) = axum::serve(listener, redirect_app).await {
+                        error!("HTTP redirect listener on {} failed: {}", redirect_addr, e);
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to bind HTTP redirect listener on {}: {}",
+                        redirect_addr, e
+                    );
+                }
+            }
+        });
+    }
+
+    info!("Starting Rupy server on https://{}", addr);
+    println!("Starting Rupy server on https://{}", addr);
+
+    #[cfg(feature = "tls-native-tls")]
+    axum_server::bind_openssl(addr, tls_config)
+        .serve(app.into_make_service())
+        .await
+        .map_err(|e| format!("TLS server error: {}", e))?;
+    #[cfg(not(feature = "tls-native-tls"))]
+    axum_server::bind_rustls(addr, tls_config)
+        .serve(app.into_make_service())
+        .await
+        .map_err(|e| format!("TLS server error: {}", e))?;
+
+    info!("Server shutdown complete");
+    println!("Server shutdown complete");
+
+    Ok(())
+}
+
 // Handle shutdown signals (Ctrl+C)
 async fn shutdown_signal() {
     use tokio::signal;
@@ -871,65 +1993,513 @@ fn record_metrics(
         config.enabled
     };
 
-    if is_enabled {
-        let service_name = {
-            let config = telemetry_config.lock().unwrap();
-            config.service_name.clone()
-        };
+    if is_enabled {
+        let service_name = {
+            let config = telemetry_config.lock().unwrap();
+            config.service_name.clone()
+        };
+
+        // Get meter and record metrics (leak the string to get 'static lifetime)
+        let meter = global::meter(Box::leak(service_name.into_boxed_str()));
+        let counter = meter
+            .u64_counter("http.server.requests")
+            .with_description("Total number of HTTP requests")
+            .build();
+        let histogram = meter
+            .f64_histogram("http.server.duration")
+            .with_description("HTTP request duration in seconds")
+            .with_unit("s")
+            .build();
+
+        counter.add(
+            1,
+            &[
+                KeyValue::new("http.method", method_str.to_string()),
+                KeyValue::new("http.route", path.to_string()),
+                KeyValue::new("http.status_code", status_code as i64),
+            ],
+        );
+
+        histogram.record(
+            duration.as_secs_f64(),
+            &[
+                KeyValue::new("http.method", method_str.to_string()),
+                KeyValue::new("http.route", path.to_string()),
+                KeyValue::new("http.status_code", status_code as i64),
+            ],
+        );
+    }
+}
+
+/// Reads W3C trace-context headers out of the request's header map, for
+/// `global::get_text_map_propagator(|p| p.extract(...))` to find a parent trace.
+struct HeaderExtractor<'a>(&'a HashMap<String, String>);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|k| k.as_str()).collect()
+    }
+}
+
+/// Writes W3C trace-context headers into an outgoing axum `HeaderMap`, for
+/// `global::get_text_map_propagator(|p| p.inject_context(...))` to stamp the
+/// active span context onto the response.
+struct HeaderMapInjector<'a>(&'a mut axum::http::HeaderMap);
+
+impl Injector for HeaderMapInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        use axum::http::{HeaderName, HeaderValue};
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(key.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// Parse a `Range: bytes=...` header value into an inclusive `(start, end)`
+/// byte range against a body of `total` bytes. Supports `start-end`,
+/// open-ended `start-` (to the end), and suffix `-length` (last N bytes)
+/// forms. Only the first range of a comma-separated list is honored.
+/// Returns `None` for a malformed or unsupported header, in which case the
+/// caller should ignore `Range` and serve the full body.
+fn parse_range(header: &str, total: usize) -> Option<(usize, usize)> {
+    let spec = header.strip_prefix("bytes=")?.split(',').next()?.trim();
+    let (start_str, end_str) = spec.split_once('-')?;
+
+    if start_str.is_empty() {
+        let suffix_len: usize = end_str.parse().ok()?;
+        if suffix_len == 0 || total == 0 {
+            return None;
+        }
+        return Some((total.saturating_sub(suffix_len), total - 1));
+    }
+
+    let start: usize = start_str.parse().ok()?;
+    let end = if end_str.is_empty() {
+        total.saturating_sub(1)
+    } else {
+        end_str.parse().ok()?
+    };
+
+    Some((start, end))
+}
+
+/// Slice `body` against an optional `Range` header, returning the status
+/// code to respond with, an optional `Content-Range` header value, and the
+/// (possibly sliced) body.
+///
+/// A missing or unparseable `Range` header serves the full body unchanged.
+/// A range whose start is beyond `body`'s length yields
+/// `416 Range Not Satisfiable` with an empty body and `Content-Range: bytes
+/// */total`; otherwise the satisfiable slice is returned as `206 Partial
+/// Content` with `Content-Range: bytes start-end/total`.
+fn slice_for_range(
+    body: Vec<u8>,
+    range_header: Option<&str>,
+) -> (StatusCode, Option<String>, Vec<u8>) {
+    let total = body.len();
+    let Some((start, end)) = range_header.and_then(|h| parse_range(h, total)) else {
+        return (StatusCode::OK, None, body);
+    };
+
+    if start >= total || start > end {
+        return (
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            Some(format!("bytes */{}", total)),
+            Vec::new(),
+        );
+    }
+
+    let end = end.min(total.saturating_sub(1));
+    (
+        StatusCode::PARTIAL_CONTENT,
+        Some(format!("bytes {}-{}/{}", start, end, total)),
+        body[start..=end].to_vec(),
+    )
+}
+
+/// Whether an `Accept` header indicates the client prefers JSON over HTML,
+/// so a template route can serve a raw JSON body instead of rendering,
+/// without needing a second route for API callers.
+///
+/// This is a simple preference check, not a full RFC 7231 `q`-weighted
+/// negotiation: whichever of `application/json`/`text/html` appears first
+/// in the header wins, and a header naming only one of the two is decided
+/// by that alone.
+fn client_prefers_json(accept: Option<&str>) -> bool {
+    let Some(accept) = accept else {
+        return false;
+    };
+
+    match (accept.find("application/json"), accept.find("text/html")) {
+        (Some(json_pos), Some(html_pos)) => json_pos < html_pos,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+/// Turn a `RhaiHandler::invoke` result into the same `(Response, status)`
+/// shape a Python handler produces: a string becomes the response body
+/// as-is, an object is rendered through `template_name` if the route has
+/// one (mirroring the Python template-route dict path) or else serialized
+/// as JSON.
+fn build_rhai_response(
+    value: serde_json::Value,
+    route_info: &RouteInfo,
+    template_config: &Arc<Mutex<TemplateConfig>>,
+    template_engine: &TemplateEngine,
+) -> (axum::response::Response, u16) {
+    match value {
+        serde_json::Value::String(body) => {
+            let mut response = axum::response::Response::new(body.into());
+            response.headers_mut().insert(
+                axum::http::header::CONTENT_TYPE,
+                axum::http::HeaderValue::from_str(&route_info.content_type)
+                    .unwrap_or(axum::http::HeaderValue::from_static("text/plain")),
+            );
+            (response, 200)
+        }
+        serde_json::Value::Object(_) if route_info.is_template => {
+            let template_dirs = template_config.lock().unwrap().template_dirs.clone();
+            let template_name = route_info.template_name.as_ref().unwrap();
+
+            match template_engine.render(&template_dirs, template_name, &value) {
+                Ok(rendered) => {
+                    let mut response = axum::response::Response::new(rendered.into());
+                    response.headers_mut().insert(
+                        axum::http::header::CONTENT_TYPE,
+                        axum::http::HeaderValue::from_str(&route_info.content_type)
+                            .unwrap_or(axum::http::HeaderValue::from_static("text/html")),
+                    );
+                    (response, 200)
+                }
+                Err(e) => {
+                    error!("Template rendering error: {:?}", e);
+                    (
+                        (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            format!("Template rendering error: {}", e),
+                        )
+                            .into_response(),
+                        500,
+                    )
+                }
+            }
+        }
+        other => {
+            let mut response = axum::response::Response::new(other.to_string().into());
+            response.headers_mut().insert(
+                axum::http::header::CONTENT_TYPE,
+                axum::http::HeaderValue::from_static("application/json"),
+            );
+            (response, 200)
+        }
+    }
+}
+
+/// Guess a file's MIME type from its extension, for static-file responses.
+/// Falls back to `application/octet-stream` for unrecognized extensions.
+fn guess_content_type(path: &std::path::Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "json" => "application/json",
+        "txt" => "text/plain",
+        "xml" => "application/xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "pdf" => "application/pdf",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "wasm" => "application/wasm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Compute a strong ETag (a quoted, hex-encoded SHA-256 digest) for `body`.
+fn compute_etag(body: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(body);
+    format!("\"{:x}\"", hasher.finalize())
+}
+
+/// Whether a response carrying `etag`/`last_modified` can be short-circuited
+/// to `304 Not Modified` given the request's conditional headers.
+///
+/// `If-None-Match` takes precedence over `If-Modified-Since` per RFC 7232
+/// §3.3: when present, `If-Modified-Since` is ignored entirely. A `*`
+/// `If-None-Match` matches any existing representation; otherwise each
+/// comma-separated (optionally weak, `W/`-prefixed) tag is compared against
+/// `etag`.
+fn is_not_modified(
+    etag: &str,
+    last_modified: Option<&str>,
+    if_none_match: Option<&str>,
+    if_modified_since: Option<&str>,
+) -> bool {
+    if let Some(if_none_match) = if_none_match {
+        let if_none_match = if_none_match.trim();
+        return if_none_match == "*"
+            || if_none_match
+                .split(',')
+                .any(|candidate| candidate.trim().trim_start_matches("W/") == etag);
+    }
+
+    match (last_modified, if_modified_since) {
+        (Some(last_modified), Some(if_modified_since)) => last_modified == if_modified_since,
+        _ => false,
+    }
+}
+
+/// Why `resolve_static_mount` couldn't serve a file for a path that matched
+/// one of its mounts.
+enum StaticMountError {
+    /// The resolved path escaped the mount's canonicalized directory.
+    Forbidden,
+    /// The mount matched but no such file exists underneath it.
+    NotFound,
+}
+
+/// Resolve `request_path` against `mounts` (`Rupy.mount_static`'s
+/// prefix -> directory table), returning the matching mount's file bytes
+/// and resolved path (for content-type sniffing). The longest matching
+/// prefix wins when mounts overlap; returns `None` when no mount matches,
+/// so the caller can fall through to the dynamic route table.
+fn resolve_static_mount(
+    mounts: &StaticMountConfig,
+    request_path: &str,
+) -> Option<Result<(Vec<u8>, PathBuf), StaticMountError>> {
+    let (prefix, directory) = mounts
+        .mounts
+        .iter()
+        .filter(|(prefix, _)| {
+            request_path
+                .strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .max_by_key(|(prefix, _)| prefix.len())?;
+
+    let remainder = request_path[prefix.len()..].trim_start_matches('/');
+    let file_path = PathBuf::from(directory).join(remainder);
+
+    // Resolve both paths to absolute, symlink-free form so a requested path
+    // like `../../etc/passwd` can't escape the mounted directory. A file
+    // that doesn't exist yet can't be canonicalized directly, so walk up to
+    // the nearest existing ancestor instead -- a missing-but-in-root path
+    // must fall through to `NotFound` (404), not `Forbidden` (403), matching
+    // `route_static`'s handling of the same case.
+    let root_canonical = std::fs::canonicalize(directory).ok();
+    let within_root = match &root_canonical {
+        None => false,
+        Some(root_canonical) => match std::fs::canonicalize(&file_path) {
+            Ok(file_canonical) => file_canonical.starts_with(root_canonical),
+            Err(_) => {
+                let mut ancestor = file_path.parent();
+                loop {
+                    match ancestor {
+                        Some(dir) => match std::fs::canonicalize(dir) {
+                            Ok(dir_canonical) => break dir_canonical.starts_with(root_canonical),
+                            Err(_) => ancestor = dir.parent(),
+                        },
+                        None => break false,
+                    }
+                }
+            }
+        },
+    };
+
+    if !within_root {
+        return Some(Err(StaticMountError::Forbidden));
+    }
 
-        // Get meter and record metrics (leak the string to get 'static lifetime)
-        let meter = global::meter(Box::leak(service_name.into_boxed_str()));
-        let counter = meter
-            .u64_counter("http.server.requests")
-            .with_description("Total number of HTTP requests")
-            .build();
-        let histogram = meter
-            .f64_histogram("http.server.duration")
-            .with_description("HTTP request duration in seconds")
-            .with_unit("s")
-            .build();
+    match std::fs::read(&file_path) {
+        Ok(contents) => Some(Ok((contents, file_path))),
+        Err(_) => Some(Err(StaticMountError::NotFound)),
+    }
+}
 
-        counter.add(
-            1,
-            &[
-                KeyValue::new("http.method", method_str.to_string()),
-                KeyValue::new("http.route", path.to_string()),
-                KeyValue::new("http.status_code", status_code as i64),
-            ],
+/// Build the OpenAPI 3.0 document served by `Rupy.enable_openapi`, walking
+/// `routes` to emit one `paths` entry per distinct templated path, with one
+/// method object per HTTP method the route supports.
+///
+/// Every registered route is included, upload and `route_static` routes as
+/// well. Static mounts from `Rupy.mount_static` are served outside route
+/// matching and don't appear in the document.
+fn build_openapi_document(routes: &[RouteInfo], config: &OpenApiConfig) -> serde_json::Value {
+    let mut paths = serde_json::Map::new();
+
+    for route_info in routes {
+        // OpenAPI templates path params as `{name}`, not our `<name>`.
+        let openapi_path = route_info
+            .path
+            .split('/')
+            .map(|segment| {
+                if segment.starts_with('<') && segment.ends_with('>') {
+                    format!("{{{}}}", &segment[1..segment.len() - 1])
+                } else {
+                    segment.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let parameters: Vec<serde_json::Value> = route_info
+            .params
+            .iter()
+            .filter(|spec| spec.location() != "body")
+            .map(|spec| {
+                serde_json::json!({
+                    "name": spec.name(),
+                    "in": spec.location(),
+                    "required": spec.is_required(),
+                    "schema": { "type": spec.openapi_type() },
+                })
+            })
+            .chain(route_info.path_params.iter().filter(|name| {
+                !route_info.params.iter().any(|spec| spec.name() == name.as_str())
+            }).map(|name| {
+                serde_json::json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" },
+                })
+            }))
+            .collect();
+
+        let mut response_content = serde_json::Map::new();
+        response_content.insert(
+            route_info.content_type.clone(),
+            match &route_info.response_example {
+                Some(example) => serde_json::json!({ "example": example }),
+                None => serde_json::json!({}),
+            },
         );
 
-        histogram.record(
-            duration.as_secs_f64(),
-            &[
-                KeyValue::new("http.method", method_str.to_string()),
-                KeyValue::new("http.route", path.to_string()),
-                KeyValue::new("http.status_code", status_code as i64),
-            ],
+        let mut responses = serde_json::Map::new();
+        responses.insert(
+            "200".to_string(),
+            serde_json::json!({
+                "description": "Successful Response",
+                "content": response_content,
+            }),
         );
+
+        let mut operation = serde_json::Map::new();
+        if let Some(summary) = &route_info.summary {
+            operation.insert("summary".to_string(), serde_json::json!(summary));
+        }
+        operation.insert("parameters".to_string(), serde_json::json!(parameters));
+        operation.insert("responses".to_string(), serde_json::json!(responses));
+
+        let path_item = paths
+            .entry(openapi_path)
+            .or_insert_with(|| serde_json::json!({}));
+        for method in &route_info.methods {
+            path_item[method.to_lowercase()] = serde_json::json!(operation);
+        }
     }
+
+    serde_json::json!({
+        "openapi": "3.0.0",
+        "info": {
+            "title": config.title,
+            "version": config.version,
+        },
+        "paths": paths,
+    })
 }
 
-// Parse cookies from Cookie header
-fn parse_cookies(cookie_header: &str) -> HashMap<String, String> {
-    let mut cookies = HashMap::new();
-    for cookie in cookie_header.split(';') {
-        let cookie = cookie.trim();
-        if let Some(eq_pos) = cookie.find('=') {
-            let name = cookie[..eq_pos].trim().to_string();
-            let value = cookie[eq_pos + 1..].trim().to_string();
-            cookies.insert(name, value);
+/// Build a `206 Partial Content`/`416 Range Not Satisfiable`/`200 OK`
+/// response for a static file's `contents`, honoring an optional `Range`
+/// header and always advertising `Accept-Ranges: bytes`. Short-circuits to
+/// `304 Not Modified` when `if_none_match` matches the file's ETag. No
+/// `Last-Modified` is known for the file, so `if_modified_since` alone never
+/// produces a 304.
+fn build_static_file_response(
+    contents: Vec<u8>,
+    content_type: &str,
+    range_header: Option<&str>,
+    if_none_match: Option<&str>,
+    if_modified_since: Option<&str>,
+) -> axum::response::Response {
+    use axum::http::header::{
+        HeaderMap, HeaderValue, ACCEPT_RANGES, CONTENT_RANGE, CONTENT_TYPE, ETAG,
+    };
+    use axum::response::IntoResponse;
+
+    let etag = compute_etag(&contents);
+    if is_not_modified(&etag, None, if_none_match, if_modified_since) {
+        let mut header_map = HeaderMap::new();
+        header_map.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+        if let Ok(value) = HeaderValue::from_str(&etag) {
+            header_map.insert(ETAG, value);
+        }
+        return (StatusCode::NOT_MODIFIED, header_map).into_response();
+    }
+
+    let (status_code, content_range, body) = slice_for_range(contents, range_header);
+
+    let mut header_map = HeaderMap::new();
+    header_map.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    if let Ok(value) = HeaderValue::from_str(content_type) {
+        header_map.insert(CONTENT_TYPE, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        header_map.insert(ETAG, value);
+    }
+    if let Some(content_range) = content_range {
+        if let Ok(value) = HeaderValue::from_str(&content_range) {
+            header_map.insert(CONTENT_RANGE, value);
         }
     }
-    cookies
+
+    (status_code, header_map, body).into_response()
 }
 
 // Build an axum response from PyResponse with headers and cookies
-fn build_response(py_response: PyResponse) -> axum::response::Response {
-    use axum::http::header::{HeaderMap, HeaderName, HeaderValue};
+#[allow(clippy::too_many_arguments)]
+fn build_response(
+    py_response: PyResponse,
+    trace_cx: &OtelContext,
+    range_header: Option<&str>,
+    if_none_match: Option<&str>,
+    if_modified_since: Option<&str>,
+    cors_config: &CorsConfig,
+    origin: Option<&str>,
+) -> axum::response::Response {
+    use axum::http::header::{
+        HeaderMap, HeaderName, HeaderValue, ACCEPT_RANGES, CACHE_CONTROL, CONTENT_RANGE,
+        ETAG, LAST_MODIFIED,
+    };
     use axum::response::IntoResponse;
 
-    let status_code = StatusCode::from_u16(py_response.status).unwrap_or(StatusCode::OK);
-    let body = py_response.body;
+    let mut status_code = StatusCode::from_u16(py_response.status).unwrap_or(StatusCode::OK);
+    let mut body = py_response.body.into_bytes();
 
     // Build header map
     let mut header_map = HeaderMap::new();
@@ -941,6 +2511,61 @@ fn build_response(py_response: PyResponse) -> axum::response::Response {
         }
     }
 
+    header_map.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+    // Reuse the handler's own ETag if it set one; otherwise derive a strong
+    // ETag from the body so every response is cache-validatable for free.
+    let etag = header_map
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| compute_etag(&body));
+    if !header_map.contains_key(ETAG) {
+        if let Ok(value) = HeaderValue::from_str(&etag) {
+            header_map.insert(ETAG, value);
+        }
+    }
+    let last_modified = header_map
+        .get(LAST_MODIFIED)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    if status_code == StatusCode::OK
+        && is_not_modified(&etag, last_modified.as_deref(), if_none_match, if_modified_since)
+    {
+        // Drop the body but keep the headers a cache needs to stay valid.
+        let mut not_modified_headers = HeaderMap::new();
+        for name in [ETAG, LAST_MODIFIED, CACHE_CONTROL] {
+            if let Some(value) = header_map.get(&name) {
+                not_modified_headers.insert(name, value.clone());
+            }
+        }
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(trace_cx, &mut HeaderMapInjector(&mut not_modified_headers));
+        });
+        apply_cors_headers(&mut not_modified_headers, cors_config, origin);
+        return (StatusCode::NOT_MODIFIED, not_modified_headers).into_response();
+    }
+
+    // Only a plain 200 response is eligible for range slicing; anything
+    // else (redirects, errors, handler-chosen statuses) is left untouched.
+    if status_code == StatusCode::OK {
+        let (range_status, content_range, sliced_body) = slice_for_range(body, range_header);
+        status_code = range_status;
+        body = sliced_body;
+        if let Some(content_range) = content_range {
+            if let Ok(value) = HeaderValue::from_str(&content_range) {
+                header_map.insert(CONTENT_RANGE, value);
+            }
+        }
+    }
+
+    // Stamp the active trace context back onto the response so the caller
+    // can correlate it with the span this request was handled under.
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(trace_cx, &mut HeaderMapInjector(&mut header_map));
+    });
+
     // Add Set-Cookie headers
     for cookie in py_response.cookies.iter() {
         if let Ok(cookie_value) = HeaderValue::from_str(cookie) {
@@ -948,87 +2573,167 @@ fn build_response(py_response: PyResponse) -> axum::response::Response {
         }
     }
 
+    apply_cors_headers(&mut header_map, cors_config, origin);
+
     (status_code, header_map, body).into_response()
 }
 
-// Render a template using Handlebars with multiple directory support
-fn render_template_with_dirs(
-    template_dirs: &[String],
-    template_name: &str,
-    context: &serde_json::Value,
-) -> Result<String, String> {
-    let mut handlebars = Handlebars::new();
+/// Pull the next chunk out of a Python iterator, returning `None` once it's
+/// exhausted. Accepts `bytes` or `str` items, falling back to `str()` for
+/// anything else.
+fn next_stream_chunk(iterator: &Py<PyAny>, py: Python) -> PyResult<Option<Vec<u8>>> {
+    match iterator.call_method0(py, "__next__") {
+        Ok(item) => {
+            if let Ok(bytes) = item.extract::<Vec<u8>>(py) {
+                Ok(Some(bytes))
+            } else if let Ok(s) = item.extract::<String>(py) {
+                Ok(Some(s.into_bytes()))
+            } else {
+                Ok(Some(item.bind(py).str()?.to_string().into_bytes()))
+            }
+        }
+        Err(e) if e.is_instance_of::<pyo3::exceptions::PyStopIteration>(py) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+// Build an axum streaming response from a PyStreamResponse, pulling chunks
+// from its Python generator as the client reads the body instead of
+// buffering the whole thing up front.
+fn build_stream_response(
+    stream_response: PyStreamResponse,
+    trace_cx: &OtelContext,
+) -> axum::response::Response {
+    use axum::http::header::{HeaderMap, HeaderName, HeaderValue};
+
+    let status_code = StatusCode::from_u16(stream_response.status).unwrap_or(StatusCode::OK);
+    let is_sse = stream_response.is_sse;
 
-    // Try to find and read the template file from the list of directories
-    let mut template_content = None;
-    let mut tried_paths = Vec::new();
+    let iterator: Py<PyAny> = Python::attach(|py| {
+        stream_response
+            .generator
+            .call_method0(py, "__iter__")
+            .unwrap_or_else(|_| stream_response.generator.clone_ref(py))
+    });
 
-    for template_dir in template_dirs {
-        let template_path = PathBuf::from(template_dir).join(template_name);
-        tried_paths.push(template_path.display().to_string());
+    let byte_stream = futures_util::stream::unfold(Some(iterator), move |state| async move {
+        let iterator = state?;
+        match Python::attach(|py| next_stream_chunk(&iterator, py)) {
+            Ok(Some(mut chunk)) => {
+                if is_sse {
+                    let mut framed = b"data: ".to_vec();
+                    framed.append(&mut chunk);
+                    framed.extend_from_slice(b"\n\n");
+                    chunk = framed;
+                }
+                Some((Ok::<_, std::io::Error>(axum::body::Bytes::from(chunk)), Some(iterator)))
+            }
+            Ok(None) => None,
+            Err(e) => {
+                error!("Error iterating stream response: {:?}", e);
+                None
+            }
+        }
+    });
 
-        if let Ok(content) = std::fs::read_to_string(&template_path) {
-            template_content = Some(content);
-            break;
+    let mut header_map = HeaderMap::new();
+    for (key, value) in stream_response.headers.iter() {
+        if let Ok(header_name) = HeaderName::from_bytes(key.as_bytes()) {
+            if let Ok(header_value) = HeaderValue::from_str(value) {
+                header_map.insert(header_name, header_value);
+            }
         }
     }
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(trace_cx, &mut HeaderMapInjector(&mut header_map));
+    });
 
-    let template_content = template_content.ok_or_else(|| {
-        format!(
-            "Failed to read template file '{}'. Tried paths: {}",
-            template_name,
-            tried_paths.join(", ")
-        )
-    })?;
+    (status_code, header_map, Body::from_stream(byte_stream)).into_response()
+}
 
-    // Register the template
-    handlebars
-        .register_template_string("template", template_content)
-        .map_err(|e| format!("Failed to parse template: {}", e))?;
+/// How many leading bytes of an uploaded file are buffered for content
+/// sniffing before being checked and written through to disk.
+const CONTENT_SNIFF_LEN: usize = 512;
 
-    // Render the template
-    handlebars
-        .render("template", context)
-        .map_err(|e| format!("Failed to render template: {}", e))
-}
+/// Identify a file's true MIME type from its leading bytes (a "magic
+/// number" sniff), independent of whatever the client's `Content-Type`
+/// claimed. Returns `None` for a prefix that doesn't match any recognized
+/// signature.
+fn sniff_mime_type(prefix: &[u8]) -> Option<&'static str> {
+    const PNG: &[u8] = &[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+
+    if prefix.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some("image/jpeg");
+    }
+    if prefix.starts_with(PNG) {
+        return Some("image/png");
+    }
+    if prefix.starts_with(b"GIF87a") || prefix.starts_with(b"GIF89a") {
+        return Some("image/gif");
+    }
+    if prefix.starts_with(b"%PDF") {
+        return Some("application/pdf");
+    }
+    if prefix.len() >= 12 && &prefix[0..4] == b"RIFF" && &prefix[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
 
-// Render a template using Handlebars (backward compatibility)
-fn render_template(
-    template_dir: &str,
-    template_name: &str,
-    context: &serde_json::Value,
-) -> Result<String, String> {
-    render_template_with_dirs(&[template_dir.to_string()], template_name, context)
+    None
 }
 
-// Helper function to convert Python dict to JSON
-fn py_dict_to_json(py: Python, py_dict: &Py<PyDict>) -> PyResult<serde_json::Value> {
-    let dict = py_dict.bind(py);
-    let mut context = serde_json::Map::new();
-
-    for (key, value) in dict.iter() {
-        let key_str = key.extract::<String>()?;
-        let json_value = if let Ok(s) = value.extract::<String>() {
-            serde_json::Value::String(s)
-        } else if let Ok(i) = value.extract::<i64>() {
-            serde_json::Value::Number(i.into())
-        } else if let Ok(f) = value.extract::<f64>() {
-            serde_json::Value::Number(
-                serde_json::Number::from_f64(f)
-                    .unwrap_or_else(|| serde_json::Number::from(0)),
-            )
-        } else if let Ok(b) = value.extract::<bool>() {
-            serde_json::Value::Bool(b)
-        } else if value.is_none() {
-            serde_json::Value::Null
-        } else {
-            // Try to convert to string as fallback
-            serde_json::Value::String(value.to_string())
-        };
-        context.insert(key_str, json_value);
+/// Check a sniffed upload field's real content type against what the client
+/// declared and against `upload_config.accepted_mime_types`.
+///
+/// Rejects outright when `prefix`'s magic bytes identify a type other than
+/// `declared_content_type`. When the prefix doesn't match any recognized
+/// signature (e.g. a text format we don't sniff) and the route declares an
+/// `accepted_mime_types` allowlist, the declared type can't be trusted
+/// either -- a real file of an accepted type would have produced a
+/// signature, so an unrecognized one is treated as a mismatch instead of
+/// silently falling back to the client's claim (the classic "rename to
+/// .png" spoof). Only a route with no allowlist at all still falls back to
+/// `declared_content_type` for an unrecognized signature.
+fn verify_sniffed_content_type(
+    prefix: &[u8],
+    declared_content_type: &str,
+    upload_config: &UploadConfig,
+) -> Result<(), String> {
+    let effective_type = match sniff_mime_type(prefix) {
+        Some(detected) if detected != declared_content_type => {
+            return Err(format!(
+                "Declared Content-Type '{}' does not match detected file type '{}'",
+                declared_content_type, detected
+            ));
+        }
+        Some(detected) => detected,
+        None if !upload_config.accepted_mime_types.is_empty() => {
+            return Err(format!(
+                "Could not verify declared Content-Type '{}': file's magic bytes don't match a recognized signature",
+                declared_content_type
+            ));
+        }
+        None => declared_content_type,
+    };
+
+    if !upload_config.accepted_mime_types.is_empty() {
+        let mime_accepted = upload_config.accepted_mime_types.iter().any(|accepted| {
+            // Support wildcard matching (e.g., "image/*")
+            if accepted.ends_with("/*") {
+                effective_type.starts_with(&accepted[..accepted.len() - 2])
+            } else {
+                effective_type == accepted
+            }
+        });
+
+        if !mime_accepted {
+            return Err(format!(
+                "File type '{}' not accepted. Accepted types: {:?}",
+                effective_type, upload_config.accepted_mime_types
+            ));
+        }
     }
 
-    Ok(serde_json::Value::Object(context))
+    Ok(())
 }
 
 // Process multipart file upload
@@ -1036,6 +2741,7 @@ async fn process_multipart_upload(
     body: Body,
     boundary: String,
     upload_config: &UploadConfig,
+    chunk_timeout_ms: Option<u64>,
 ) -> Result<Vec<PyUploadFile>, String> {
     // Convert Body to Stream for multer
     let stream = body.into_data_stream();
@@ -1057,49 +2763,36 @@ async fn process_multipart_upload(
             .map(|s| s.to_string())
             .unwrap_or_else(|| "application/octet-stream".to_string());
 
-        // Check if MIME type is accepted
-        if !upload_config.accepted_mime_types.is_empty() {
-            let mime_accepted = upload_config.accepted_mime_types.iter().any(|accepted| {
-                // Support wildcard matching (e.g., "image/*")
-                if accepted.ends_with("/*") {
-                    let prefix = &accepted[..accepted.len() - 2];
-                    content_type.starts_with(prefix)
-                } else {
-                    &content_type == accepted
-                }
-            });
-
-            if !mime_accepted {
-                return Err(format!(
-                    "File type '{}' not accepted. Accepted types: {:?}",
-                    content_type, upload_config.accepted_mime_types
-                ));
-            }
-        }
-
-        // Create a temporary file in the upload directory
-        let upload_dir = PathBuf::from(&upload_config.upload_dir);
-        std::fs::create_dir_all(&upload_dir)
-            .map_err(|e| format!("Failed to create upload directory: {}", e))?;
-
-        let mut temp_file = NamedTempFile::new_in(&upload_dir)
-            .map_err(|e| format!("Failed to create temp file: {}", e))?;
+        // Open a sink for whichever backend this route is configured with
+        // (local disk or an S3-compatible object store).
+        let mut sink = storage::UploadSink::new(&upload_config.backend).await?;
 
         let mut total_size: u64 = 0;
-
-        // Stream the file data to disk without loading it all into memory
-        while let Some(chunk) = field
-            .chunk()
+        // Buffered until we have enough bytes to sniff the real content type
+        // (or the field ends first), then checked and written through.
+        let mut sniff_buf: Vec<u8> = Vec::with_capacity(CONTENT_SNIFF_LEN);
+        let mut sniff_checked = false;
+
+        // Stream the file data to the backend without loading it all into memory
+        while let Some(chunk) = match chunk_timeout_ms {
+            Some(ms) => tokio::time::timeout(
+                std::time::Duration::from_millis(ms),
+                field.chunk(),
+            )
             .await
-            .map_err(|e| format!("Error reading file chunk: {}", e))?
-        {
+            .map_err(|_| format!("Upload stalled: no data received for {}ms", ms))?
+            .map_err(|e| format!("Error reading file chunk: {}", e))?,
+            None => field
+                .chunk()
+                .await
+                .map_err(|e| format!("Error reading file chunk: {}", e))?,
+        } {
             let chunk_size = chunk.len() as u64;
             total_size += chunk_size;
 
             // Check size limit
             if let Some(max_size) = upload_config.max_size {
                 if total_size > max_size {
-                    // Clean up the temp file (it will be deleted when temp_file is dropped)
                     return Err(format!(
                         "File size ({} bytes) exceeds maximum allowed size ({} bytes)",
                         total_size, max_size
@@ -1107,27 +2800,26 @@ async fn process_multipart_upload(
                 }
             }
 
-            temp_file
-                .write_all(&chunk)
-                .map_err(|e| format!("Failed to write to temp file: {}", e))?;
+            if sniff_checked {
+                sink.write_chunk(&chunk).await?;
+            } else {
+                sniff_buf.extend_from_slice(&chunk);
+                if sniff_buf.len() >= CONTENT_SNIFF_LEN {
+                    verify_sniffed_content_type(&sniff_buf, &content_type, upload_config)?;
+                    sniff_checked = true;
+                    sink.write_chunk(&sniff_buf).await?;
+                }
+            }
         }
 
-        temp_file
-            .flush()
-            .map_err(|e| format!("Failed to flush temp file: {}", e))?;
+        if !sniff_checked {
+            verify_sniffed_content_type(&sniff_buf, &content_type, upload_config)?;
+            sink.write_chunk(&sniff_buf).await?;
+        }
 
-        // Persist the temp file (prevent it from being deleted)
-        let persisted_path = temp_file
-            .into_temp_path()
-            .keep()
-            .map_err(|e| format!("Failed to persist temp file: {}", e))?;
+        let path = sink.finish(&filename).await?;
 
-        let upload_file = PyUploadFile {
-            filename,
-            content_type,
-            size: total_size,
-            path: persisted_path.to_string_lossy().to_string(),
-        };
+        let upload_file = PyUploadFile::from_parts(filename, content_type, total_size, path, Vec::new());
 
         uploaded_files.push(upload_file);
     }
@@ -1135,14 +2827,72 @@ async fn process_multipart_upload(
     Ok(uploaded_files)
 }
 
+/// Run the authenticator (if any) against `request` and check the result
+/// against `required_permissions`.
+///
+/// Only a route declaring a non-empty `required_permissions` list is
+/// actually gated: an unconfigured authenticator, a rejection, or a missing
+/// permission only produce an `Err` response when permissions are required.
+/// A resolved `Identity` is returned on `Ok(Some(..))` so the caller can
+/// attach it to the request before dispatching to the handler.
+fn enforce_auth(
+    authenticator: &Option<Py<PyAny>>,
+    py: Python,
+    request: &PyRequest,
+    required_permissions: &[String],
+) -> Result<Option<auth::Identity>, (axum::response::Response, u16)> {
+    match auth::authenticate(authenticator, py, request) {
+        auth::AuthOutcome::NotConfigured => {
+            if required_permissions.is_empty() {
+                Ok(None)
+            } else {
+                error!("No authenticator configured for a route requiring permissions");
+                Err((
+                    (StatusCode::FORBIDDEN, "No authenticator configured").into_response(),
+                    403,
+                ))
+            }
+        }
+        auth::AuthOutcome::Rejected(reason) => {
+            if required_permissions.is_empty() {
+                Ok(None)
+            } else {
+                error!("Authentication failed: {}", reason);
+                Err(((StatusCode::UNAUTHORIZED, "Unauthorized").into_response(), 401))
+            }
+        }
+        auth::AuthOutcome::Allowed(identity) => {
+            if required_permissions.is_empty() {
+                Ok(Some(identity))
+            } else if let Err(reason) = auth::check_permissions(&identity, required_permissions) {
+                warn!(
+                    "Authorization failed for user '{}': {}",
+                    identity.user_id, reason
+                );
+                Err(((StatusCode::FORBIDDEN, reason).into_response(), 403))
+            } else {
+                Ok(Some(identity))
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn handler_request(
     method: Method,
     uri: Uri,
     request: Request,
     routes: Arc<Mutex<Vec<RouteInfo>>>,
     middlewares: Arc<Mutex<Vec<MiddlewareInfo>>>,
+    error_handlers: Arc<Mutex<HashMap<u16, Py<PyAny>>>>,
     telemetry_config: Arc<Mutex<TelemetryConfig>>,
     template_config: Arc<Mutex<TemplateConfig>>,
+    template_engine: Arc<TemplateEngine>,
+    authenticator: Arc<Mutex<Option<Py<PyAny>>>>,
+    default_timeout_ms: Arc<Mutex<Option<u64>>>,
+    cors_config: Arc<Mutex<CorsConfig>>,
+    static_mounts: Arc<Mutex<StaticMountConfig>>,
+    openapi_config: Arc<Mutex<OpenApiConfig>>,
 ) -> axum::response::Response {
     let start_time = Instant::now();
     let path = uri.path().to_string();
@@ -1165,11 +2915,20 @@ async fn handler_request(
 
     // Parse cookies from Cookie header
     let cookies = if let Some(cookie_header) = headers.get("cookie") {
-        parse_cookies(cookie_header)
+        request::parse_cookies(cookie_header)
     } else {
         HashMap::new()
     };
 
+    // Extract the W3C trace-context from the incoming request (if any) and
+    // open this request's span as a child of it, so rupy participates in
+    // distributed traces spanning multiple services.
+    let parent_cx =
+        global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(&headers)));
+    let otel_span = global::tracer("rupy")
+        .start_with_context(format!("{} {}", method_str, path), &parent_cx);
+    let trace_cx = parent_cx.with_span(otel_span);
+
     // Create a span for this request
     let span = span!(
         Level::INFO,
@@ -1186,6 +2945,78 @@ async fn handler_request(
         method_str, path, user_agent
     );
 
+    let cors_snapshot = cors_config.lock().unwrap().clone();
+    let origin = headers.get("origin").cloned();
+
+    // CORS preflight: an `OPTIONS` request carrying `Access-Control-Request-
+    // Method` is answered directly with a `204`, before any route is
+    // matched or dispatched to.
+    if cors_snapshot.enabled && method == Method::OPTIONS {
+        if let Some(requested_method) = headers.get("access-control-request-method").cloned() {
+            let resp = build_cors_preflight_response(
+                &cors_snapshot,
+                origin.as_deref(),
+                Some(requested_method.as_str()),
+                headers.get("access-control-request-headers").map(|s| s.as_str()),
+            );
+            let duration = start_time.elapsed();
+            record_metrics(&telemetry_config, &method_str, &path, 204, duration);
+            info!("Request completed: 204 - Duration: {:?}", duration);
+            return resp;
+        }
+    }
+
+    // The OpenAPI document (`Rupy.enable_openapi`) is a special-cased route,
+    // checked before the route table so it doesn't need to be registered
+    // (and can't be shadowed) like an ordinary handler.
+    {
+        let openapi_snapshot = openapi_config.lock().unwrap().clone();
+        if openapi_snapshot.enabled && path == openapi_snapshot.path && method == Method::GET {
+            let routes_snapshot = routes.lock().unwrap().clone();
+            let document = build_openapi_document(&routes_snapshot, &openapi_snapshot);
+            let resp = (
+                [("content-type", "application/json")],
+                document.to_string(),
+            )
+                .into_response();
+
+            let duration = start_time.elapsed();
+            record_metrics(&telemetry_config, &method_str, &path, 200, duration);
+            info!("Request completed: 200 - Duration: {:?}", duration);
+            return resp;
+        }
+    }
+
+    // Static mounts (`Rupy.mount_static`) are checked before route matching,
+    // so a mounted prefix takes priority over an overlapping dynamic route.
+    {
+        let mounts_snapshot = static_mounts.lock().unwrap().clone();
+        if let Some(result) = resolve_static_mount(&mounts_snapshot, &path) {
+            let mut resp = match result {
+                Ok((contents, file_path)) => build_static_file_response(
+                    contents,
+                    guess_content_type(&file_path),
+                    headers.get("range").map(|s| s.as_str()),
+                    headers.get("if-none-match").map(|s| s.as_str()),
+                    headers.get("if-modified-since").map(|s| s.as_str()),
+                ),
+                Err(StaticMountError::Forbidden) => {
+                    (StatusCode::FORBIDDEN, "Forbidden").into_response()
+                }
+                Err(StaticMountError::NotFound) => {
+                    (StatusCode::NOT_FOUND, "Not Found").into_response()
+                }
+            };
+            apply_cors_headers(resp.headers_mut(), &cors_snapshot, origin.as_deref());
+
+            let status_code = resp.status().as_u16();
+            let duration = start_time.elapsed();
+            record_metrics(&telemetry_config, &method_str, &path, status_code, duration);
+            info!("Request completed: {} - Duration: {:?}", status_code, duration);
+            return resp;
+        }
+    }
+
     // Try to find a matching route early to check if it's an upload route
     let matched_route = {
         let routes_lock = routes.lock().unwrap();
@@ -1240,20 +3071,49 @@ async fn handler_request(
             };
 
             let upload_config = route_info.upload_config.as_ref().unwrap();
+            let chunk_timeout_ms = route_info
+                .timeout_ms
+                .or_else(|| *default_timeout_ms.lock().unwrap());
+
+            // Run authentication before the multipart body is read at all,
+            // so an unauthenticated/unauthorized client is rejected before
+            // triggering a full upload to disk or object storage.
+            let mut py_request = PyRequest::from_parts(
+                method_str.clone(),
+                path.clone(),
+                Vec::new(),
+                headers.clone(),
+                cookies.clone(),
+            );
+            let auth_outcome = Python::attach(|py| {
+                let auth_callback = {
+                    let guard = authenticator.lock().unwrap();
+                    guard.as_ref().map(|cb| cb.clone_ref(py))
+                };
+                enforce_auth(&auth_callback, py, &py_request, &route_info.permissions)
+            });
+            match auth_outcome {
+                Ok(Some(identity)) => py_request.set_identity(identity),
+                Ok(None) => {}
+                Err((response, status)) => {
+                    let duration = start_time.elapsed();
+                    record_metrics(&telemetry_config, &method_str, &path, status, duration);
+                    info!("Request completed: {} - Duration: {:?}", status, duration);
+                    return response;
+                }
+            }
 
             // Process the multipart upload
-            match process_multipart_upload(request.into_body(), boundary, upload_config).await {
+            match process_multipart_upload(
+                request.into_body(),
+                boundary,
+                upload_config,
+                chunk_timeout_ms,
+            )
+            .await
+            {
                 Ok(uploaded_files) => {
                     let resp = Python::attach(|py| {
-                        // Create PyRequest with method, path, and headers
-                        let py_request = PyRequest {
-                            method: method_str.clone(),
-                            path: path.clone(),
-                            body: String::new(),
-                            headers: headers.clone(),
-                            cookies: cookies.clone(),
-                        };
-
                         // Convert uploaded files to Python objects
                         let py_files = pyo3::types::PyList::empty(py);
                         for file in uploaded_files {
@@ -1268,19 +3128,32 @@ async fn handler_request(
                             Ok(response) => {
                                 if let Ok(py_response) = response.extract::<PyResponse>(py) {
                                     let status_u16 = py_response.status;
-                                    (build_response(py_response), status_u16)
+                                    (
+                                        build_response(
+                                            py_response,
+                                            &trace_cx,
+                                            headers.get("range").map(|s| s.as_str()),
+                                            headers.get("if-none-match").map(|s| s.as_str()),
+                                            headers.get("if-modified-since").map(|s| s.as_str()),
+                                            &cors_snapshot,
+                                            origin.as_deref(),
+                                        ),
+                                        status_u16,
+                                    )
                                 } else if let Ok(response_str) = response.extract::<String>(py) {
-                                    ((StatusCode::OK, response_str).into_response(), 200)
+                                    let mut resp =
+                                        (StatusCode::OK, response_str).into_response();
+                                    apply_cors_headers(resp.headers_mut(), &cors_snapshot, origin.as_deref());
+                                    (resp, 200)
                                 } else {
                                     error!("Invalid response from upload handler");
-                                    (
-                                        (
-                                            StatusCode::INTERNAL_SERVER_ERROR,
-                                            "Invalid response from handler",
-                                        )
-                                            .into_response(),
-                                        500,
+                                    let mut resp = (
+                                        StatusCode::INTERNAL_SERVER_ERROR,
+                                        "Invalid response from handler",
                                     )
+                                        .into_response();
+                                    apply_cors_headers(resp.headers_mut(), &cors_snapshot, origin.as_deref());
+                                    (resp, 500)
                                 }
                             }
                             Err(e) => {
@@ -1310,18 +3183,61 @@ async fn handler_request(
         }
     }
 
+    // Serve static-file routes directly from disk, without running them
+    // through the Python handler pipeline.
+    if let Some((ref route_info, ref param_values)) = matched_route {
+        if route_info.is_static {
+            let static_dir = route_info.static_dir.as_deref().unwrap_or_default();
+            let requested = param_values.first().map(|s| s.as_str()).unwrap_or_default();
+            let file_path = PathBuf::from(static_dir).join(requested);
+
+            // Resolve both paths to absolute, symlink-free form so a
+            // requested path like `../../etc/passwd` can't escape `static_dir`.
+            let root_canonical = std::fs::canonicalize(static_dir).ok();
+            let file_canonical = std::fs::canonicalize(&file_path).ok();
+            let within_root = match (&root_canonical, &file_canonical) {
+                (Some(root), Some(file)) => file.starts_with(root),
+                _ => false,
+            };
+
+            let mut resp = if !within_root {
+                (StatusCode::NOT_FOUND, "Not Found").into_response()
+            } else {
+                match std::fs::read(&file_path) {
+                    Ok(contents) => build_static_file_response(
+                        contents,
+                        guess_content_type(&file_path),
+                        headers.get("range").map(|s| s.as_str()),
+                        headers.get("if-none-match").map(|s| s.as_str()),
+                        headers.get("if-modified-since").map(|s| s.as_str()),
+                    ),
+                    Err(_) => (StatusCode::NOT_FOUND, "Not Found").into_response(),
+                }
+            };
+            apply_cors_headers(resp.headers_mut(), &cors_snapshot, origin.as_deref());
+
+            let status_code = resp.status().as_u16();
+            let duration = start_time.elapsed();
+            record_metrics(&telemetry_config, &method_str, &path, status_code, duration);
+            info!("Request completed: {} - Duration: {:?}", status_code, duration);
+            return resp;
+        }
+    }
+
     // Extract body for non-upload methods that support it (POST, PUT, PATCH, DELETE)
+    // Kept as raw bytes (rather than lossily converted to a String) so binary
+    // bodies such as multipart/form-data survive intact for PyRequest::multipart().
     let body = if method == Method::POST
         || method == Method::PUT
         || method == Method::PATCH
         || method == Method::DELETE
     {
         match axum::body::to_bytes(request.into_body(), usize::MAX).await {
-            Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
-            Err(_) => String::new(),
+            Ok(bytes) => bytes.to_vec(),
+            Err(_) => Vec::new(),
         }
     } else {
-        String::new()
+        Vec::new()
     };
 
     // Execute middlewares
@@ -1332,13 +3248,13 @@ async fn handler_request(
 
         Python::attach(|py| {
             // Create PyRequest with method, path, body, headers, and cookies
-            let mut py_request = PyRequest {
-                method: method_str.clone(),
-                path: path.clone(),
-                body: body.clone(),
-                headers: headers.clone(),
-                cookies: cookies.clone(),
-            };
+            let mut py_request = PyRequest::from_parts(
+                method_str.clone(),
+                path.clone(),
+                body.clone(),
+                headers.clone(),
+                cookies.clone(),
+            );
 
             // Execute each middleware in order
             for middleware_info in middlewares_list.iter() {
@@ -1349,7 +3265,18 @@ async fn handler_request(
                         // Check if middleware returned a Response (early termination)
                         if let Ok(py_response) = response.extract::<PyResponse>(py) {
                             let status_u16 = py_response.status;
-                            return Some((build_response(py_response), status_u16));
+                            return Some((
+                                build_response(
+                                    py_response,
+                                    &trace_cx,
+                                    headers.get("range").map(|s| s.as_str()),
+                                    headers.get("if-none-match").map(|s| s.as_str()),
+                                    headers.get("if-modified-since").map(|s| s.as_str()),
+                                    &cors_snapshot,
+                                    origin.as_deref(),
+                                ),
+                                status_u16,
+                            ));
                         }
                         // Otherwise, middleware might have modified the request
                         // Try to extract updated request
@@ -1387,20 +3314,161 @@ async fn handler_request(
     let (response, status_code) = if let Some((route_info, param_values)) = matched_route {
         let handler_span =
             span!(Level::INFO, "handler_execution", handler.route = %route_info.path);
-        let _handler_enter = handler_span.enter();
 
-        let resp = Python::attach(|py| {
+        // A route's own `timeout_ms` overrides the app-wide default set via
+        // `Rupy.set_request_timeout`.
+        let effective_timeout_ms = route_info
+            .timeout_ms
+            .or_else(|| *default_timeout_ms.lock().unwrap());
+        let route_path = route_info.path.clone();
+        // Shadow with owned copies so the `move` dispatch closure below can
+        // take them without stealing `method_str`/`path` from the metrics
+        // recording that happens after this match.
+        let method_str = method_str.clone();
+        let path = path.clone();
+        let cors_snapshot = cors_snapshot.clone();
+        let origin = origin.clone();
+        let error_handlers = error_handlers.clone();
+
+        // Run the handler on the blocking thread pool rather than inline, so
+        // a `tokio::time::timeout` around it can actually race a deadline
+        // against synchronous Python code instead of being stuck behind it
+        // on the same task. Note this can't truly cancel the handler: on
+        // timeout the client gets its `504` immediately, but the spawned
+        // task keeps running to completion in the background.
+        let dispatch = move || {
+            let _handler_enter = handler_span.enter();
+
+            // `route_script` routes are evaluated here, before the GIL is
+            // ever acquired, so a Rhai one-liner doesn't pay the Python
+            // interpreter's cost.
+            if let Some(rhai_handler) = &route_info.rhai_handler {
+                // Only a route that declares permissions takes the GIL, to
+                // run the authenticator.
+                if !route_info.permissions.is_empty() {
+                    let auth_result = Python::attach(|py| {
+                        let auth_callback = {
+                            let guard = authenticator.lock().unwrap();
+                            guard.as_ref().map(|cb| cb.clone_ref(py))
+                        };
+                        let auth_request = PyRequest::from_parts(
+                            method_str.clone(),
+                            path.clone(),
+                            body.clone(),
+                            headers.clone(),
+                            cookies.clone(),
+                        );
+                        enforce_auth(&auth_callback, py, &auth_request, &route_info.permissions)
+                    });
+                    if let Err(rejection) = auth_result {
+                        return rejection;
+                    }
+                }
+
+                let rhai_request = RhaiRequest {
+                    method: method_str.clone(),
+                    path: path.clone(),
+                    query: uri.query().unwrap_or("").to_string(),
+                    headers: headers.clone(),
+                    body: String::from_utf8_lossy(&body).to_string(),
+                };
+
+                return match rhai_handler.invoke(&rhai_request) {
+                    Ok(value) => build_rhai_response(
+                        value,
+                        &route_info,
+                        &template_config,
+                        &template_engine,
+                    ),
+                    Err(e) => {
+                        error!("Rhai script error: {}", e);
+                        (
+                            (StatusCode::INTERNAL_SERVER_ERROR, format!("Script error: {}", e))
+                                .into_response(),
+                            500,
+                        )
+                    }
+                };
+            }
+
+            Python::attach(|py| {
             // Create PyRequest with method, path, body, headers, and cookies
-            let py_request = PyRequest {
-                method: method_str.clone(),
-                path: path.clone(),
-                body,
-                headers: headers.clone(),
-                cookies: cookies.clone(),
+            // (kept around, cloned, in case a registered 500 error handler
+            // needs its own copy below)
+            let mut py_request = PyRequest::from_parts(
+                method_str.clone(),
+                path.clone(),
+                body.clone(),
+                headers.clone(),
+                cookies.clone(),
+            );
+
+            let auth_callback = {
+                let guard = authenticator.lock().unwrap();
+                guard.as_ref().map(|cb| cb.clone_ref(py))
             };
+            match enforce_auth(&auth_callback, py, &py_request, &route_info.permissions) {
+                Ok(Some(identity)) => py_request.set_identity(identity),
+                Ok(None) => {}
+                Err((response, status)) => return (response, status),
+            }
 
             // Call the handler with the request and path parameters
-            let result = if param_values.is_empty() {
+            let result = if !route_info.params.is_empty() {
+                // Validate and coerce the declared param schema, then pass
+                // the request and the resulting dict to the handler.
+                let path_param_map: HashMap<String, String> = route_info
+                    .path_params
+                    .iter()
+                    .cloned()
+                    .zip(param_values.iter().cloned())
+                    .collect();
+                let body_json = py_request.parsed_json_body();
+
+                match validation::validate_params(
+                    &route_info.params,
+                    &path_param_map,
+                    uri.query(),
+                    body_json.as_ref(),
+                ) {
+                    Ok(validated) => {
+                        let dict = match request::json_value_to_py(
+                            py,
+                            &serde_json::Value::Object(validated),
+                        ) {
+                            Ok(dict) => dict,
+                            Err(e) => {
+                                error!("Failed to build validated params dict: {:?}", e);
+                                return (
+                                    (
+                                        StatusCode::INTERNAL_SERVER_ERROR,
+                                        "Internal Server Error",
+                                    )
+                                        .into_response(),
+                                    500,
+                                );
+                            }
+                        };
+                        route_info.handler.call1(py, (py_request, dict))
+                    }
+                    Err(e) => {
+                        let body = json!({
+                            "error": "validation_error",
+                            "field": e.field,
+                            "message": e.message,
+                        });
+                        return (
+                            (
+                                StatusCode::BAD_REQUEST,
+                                [(axum::http::header::CONTENT_TYPE, "application/json")],
+                                body.to_string(),
+                            )
+                                .into_response(),
+                            400,
+                        );
+                    }
+                }
+            } else if param_values.is_empty() {
                 // No parameters, just pass the request
                 route_info.handler.call1(py, (py_request,))
             } else {
@@ -1420,42 +3488,30 @@ async fn handler_request(
                     if route_info.is_template {
                         // Handler should return a dict for template rendering
                         if let Ok(py_dict) = response.cast_bound::<PyDict>(py) {
-                            // Convert PyDict to serde_json::Value
-                            let mut context = serde_json::Map::new();
-                            for (key, value) in py_dict.iter() {
-                                if let Ok(key_str) = key.extract::<String>() {
-                                    // Try to extract different types
-                                    let json_value = if let Ok(s) = value.extract::<String>() {
-                                        serde_json::Value::String(s)
-                                    } else if let Ok(i) = value.extract::<i64>() {
-                                        serde_json::Value::Number(i.into())
-                                    } else if let Ok(f) = value.extract::<f64>() {
-                                        if let Some(n) = serde_json::Number::from_f64(f) {
-                                            serde_json::Value::Number(n)
-                                        } else {
-                                            serde_json::Value::String(f.to_string())
-                                        }
-                                    } else if let Ok(b) = value.extract::<bool>() {
-                                        serde_json::Value::Bool(b)
-                                    } else if value.is_none() {
-                                        serde_json::Value::Null
-                                    } else {
-                                        // Fallback to string representation
-                                        serde_json::Value::String(value.to_string())
-                                    };
-                                    context.insert(key_str, json_value);
-                                }
+                            // Convert PyDict (recursing into nested dicts/lists) to serde_json::Value
+                            let context = template::py_any_to_json(py_dict.as_any())
+                                .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new()));
+
+                            // A client that explicitly prefers JSON (e.g. an
+                            // API caller hitting the same route a browser
+                            // renders as HTML) gets the handler's context
+                            // serialized directly, skipping Handlebars entirely.
+                            if client_prefers_json(headers.get("accept").map(|s| s.as_str())) {
+                                let mut response =
+                                    axum::response::Response::new(context.to_string().into());
+                                response.headers_mut().insert(
+                                    axum::http::header::CONTENT_TYPE,
+                                    axum::http::HeaderValue::from_static("application/json"),
+                                );
+                                apply_cors_headers(response.headers_mut(), &cors_snapshot, origin.as_deref());
+                                return (response, 200);
                             }
 
                             // Render the template
                             let template_dirs = template_config.lock().unwrap().template_dirs.clone();
                             let template_name = route_info.template_name.as_ref().unwrap();
 
-                            match render_template_with_dirs(
-                                &template_dirs,
-                                template_name,
-                                &serde_json::Value::Object(context),
-                            ) {
+                            match template_engine.render(&template_dirs, template_name, &context) {
                                 Ok(rendered) => {
                                     let mut response =
                                         axum::response::Response::new(rendered.into());
@@ -1464,6 +3520,7 @@ async fn handler_request(
                                         axum::http::HeaderValue::from_str(&route_info.content_type)
                                             .unwrap(),
                                     );
+                                    apply_cors_headers(response.headers_mut(), &cors_snapshot, origin.as_deref());
                                     (response, 200)
                                 }
                                 Err(e) => {
@@ -1493,11 +3550,31 @@ async fn handler_request(
                         // Extract the response for non-template routes
                         if let Ok(py_response) = response.extract::<PyResponse>(py) {
                             let status_u16 = py_response.status;
-                            (build_response(py_response), status_u16)
+                            (
+                                build_response(
+                                    py_response,
+                                    &trace_cx,
+                                    headers.get("range").map(|s| s.as_str()),
+                                    headers.get("if-none-match").map(|s| s.as_str()),
+                                    headers.get("if-modified-since").map(|s| s.as_str()),
+                                    &cors_snapshot,
+                                    origin.as_deref(),
+                                ),
+                                status_u16,
+                            )
+                        } else if let Ok(stream_response) = response.extract::<PyStreamResponse>(py)
+                        {
+                            let status_u16 = stream_response.status;
+                            let mut response = build_stream_response(stream_response, &trace_cx);
+                            apply_cors_headers(response.headers_mut(), &cors_snapshot, origin.as_deref());
+                            (response, status_u16)
                         } else {
                             // Try to convert to string
                             if let Ok(response_str) = response.extract::<String>(py) {
-                                ((StatusCode::OK, response_str).into_response(), 200)
+                                let mut response =
+                                    (StatusCode::OK, response_str).into_response();
+                                apply_cors_headers(response.headers_mut(), &cors_snapshot, origin.as_deref());
+                                (response, 200)
                             } else {
                                 error!("Invalid response from handler");
                                 (
@@ -1514,20 +3591,116 @@ async fn handler_request(
                 }
                 Err(e) => {
                     error!("Error calling Python handler: {:?}", e);
-                    (
+                    let fallback = (
                         (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
                             .into_response(),
                         500,
-                    )
+                    );
+                    let registered = error_handlers.lock().unwrap().get(&500).map(|h| h.clone_ref(py));
+                    match registered {
+                        Some(handler) => {
+                            let error_request = PyRequest::from_parts(
+                                method_str.clone(),
+                                path.clone(),
+                                body.clone(),
+                                headers.clone(),
+                                cookies.clone(),
+                            );
+                            invoke_error_handler(
+                                py,
+                                &handler,
+                                error_request,
+                                500,
+                                &trace_cx,
+                                &cors_snapshot,
+                                origin.as_deref(),
+                            )
+                            .unwrap_or(fallback)
+                        }
+                        None => fallback,
+                    }
                 }
             }
-        });
+            })
+        };
+
+        let resp = match effective_timeout_ms {
+            Some(ms) => {
+                let join_handle = tokio::task::spawn_blocking(dispatch);
+                match tokio::time::timeout(std::time::Duration::from_millis(ms), join_handle).await
+                {
+                    Ok(Ok(result)) => result,
+                    Ok(Err(e)) => {
+                        error!("Handler task for '{}' panicked: {:?}", route_path, e);
+                        (
+                            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+                                .into_response(),
+                            500,
+                        )
+                    }
+                    Err(_) => {
+                        warn!(
+                            "Request to '{}' exceeded its {}ms timeout; handler left running in the background",
+                            route_path, ms
+                        );
+                        // `Rupy.set_request_timeout`'s own request asks for a 504
+                        // here (the app gave up waiting on its own handler, the
+                        // same shape as a gateway timing out on an upstream), so
+                        // that's what's recorded via `record_metrics` below --
+                        // superseding the 408 an earlier pass had used.
+                        (
+                            (StatusCode::GATEWAY_TIMEOUT, "Gateway Timeout").into_response(),
+                            504,
+                        )
+                    }
+                }
+            }
+            None => dispatch(),
+        };
 
         resp
     } else {
-        // No route matched or method not supported, return 404
-        let resp = handler_404(Uri::from_maybe_shared(path.clone()).unwrap()).await;
-        (resp, 404)
+        // No route matched or method not supported: use a registered
+        // error_handler(404) if one exists, otherwise the built-in default.
+        let registered = error_handlers
+            .lock()
+            .unwrap()
+            .get(&404)
+            .map(|h| Python::attach(|py| h.clone_ref(py)));
+
+        match registered {
+            Some(handler) => {
+                let py_request = PyRequest::from_parts(
+                    method_str.clone(),
+                    path.clone(),
+                    body.clone(),
+                    headers.clone(),
+                    cookies.clone(),
+                );
+                let invoked = Python::attach(|py| {
+                    invoke_error_handler(
+                        py,
+                        &handler,
+                        py_request,
+                        404,
+                        &trace_cx,
+                        &cors_snapshot,
+                        origin.as_deref(),
+                    )
+                });
+                match invoked {
+                    Some(result) => result,
+                    None => {
+                        let resp = handler_404(Uri::from_maybe_shared(path.clone()).unwrap()).await;
+                        (resp, 404)
+                    }
+                }
+            }
+            None => {
+                let resp = handler_404(Uri::from_maybe_shared(path.clone()).unwrap()).await;
+                (resp, 404)
+            }
+        }
     };
 
     // Record metrics
@@ -1573,5 +3746,10 @@ fn rupy(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyRequest>()?;
     m.add_class::<PyResponse>()?;
     m.add_class::<PyUploadFile>()?;
+    m.add_class::<PyStreamResponse>()?;
+    m.add_class::<cookie::Cookie>()?;
+    m.add_class::<session::Session>()?;
+    m.add_class::<auth::Identity>()?;
+    m.add_class::<validation::ParamSpec>()?;
     Ok(())
 }
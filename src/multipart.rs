@@ -0,0 +1,169 @@
+use crate::PyUploadFile;
+use std::collections::HashMap;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Files larger than this are spooled to a temp file instead of being held in memory.
+const INLINE_MAX_SIZE: usize = 2 * 1024 * 1024; // 2 MiB
+
+/// Extract the `boundary` parameter from a `multipart/form-data` Content-Type header
+pub(crate) fn extract_boundary(content_type: &str) -> Option<String> {
+    let boundary_start = content_type.find("boundary=")?;
+    let boundary_str = content_type[boundary_start + 9..].trim();
+
+    if let Some(rest) = boundary_str.strip_prefix('"') {
+        let end_quote = rest.find('"')?;
+        Some(rest[..end_quote].to_string())
+    } else {
+        Some(
+            boundary_str
+                .split(';')
+                .next()
+                .unwrap_or(boundary_str)
+                .trim()
+                .to_string(),
+        )
+    }
+}
+
+fn find_all(haystack: &[u8], needle: &[u8]) -> Vec<usize> {
+    let mut positions = Vec::new();
+    if needle.is_empty() || haystack.len() < needle.len() {
+        return positions;
+    }
+
+    let mut start = 0;
+    while start + needle.len() <= haystack.len() {
+        if &haystack[start..start + needle.len()] == needle {
+            positions.push(start);
+            start += needle.len();
+        } else {
+            start += 1;
+        }
+    }
+    positions
+}
+
+/// Parse the `Content-Disposition`/`Content-Type` header block of one part
+fn parse_header_block(header_block: &[u8]) -> Option<(String, Option<String>, String)> {
+    let header_str = String::from_utf8_lossy(header_block);
+    let mut name = None;
+    let mut filename = None;
+    let mut content_type = "text/plain".to_string();
+
+    for line in header_str.split("\r\n") {
+        let lower = line.to_ascii_lowercase();
+        if lower.starts_with("content-disposition:") {
+            for attr in line.split(';').skip(1) {
+                let attr = attr.trim();
+                if let Some(value) = attr.strip_prefix("name=") {
+                    name = Some(value.trim_matches('"').to_string());
+                } else if let Some(value) = attr.strip_prefix("filename=") {
+                    filename = Some(value.trim_matches('"').to_string());
+                }
+            }
+        } else if lower.starts_with("content-type:") {
+            content_type = line[line.find(':').unwrap() + 1..].trim().to_string();
+        }
+    }
+
+    name.map(|n| (n, filename, content_type))
+}
+
+/// Split a fully-buffered multipart body into the raw bytes of each part,
+/// dropping the preamble before the first boundary and the epilogue after
+/// the closing `--boundary--`.
+fn split_parts<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let delimiter_bytes = delimiter.as_bytes();
+    let positions = find_all(body, delimiter_bytes);
+
+    let mut parts = Vec::new();
+    for window in positions.windows(2) {
+        let part_start = window[0] + delimiter_bytes.len();
+        let part_end = window[1];
+        if part_start > part_end {
+            continue;
+        }
+
+        let mut part = &body[part_start..part_end];
+        if let Some(stripped) = part.strip_prefix(b"\r\n") {
+            part = stripped;
+        }
+        if let Some(stripped) = part.strip_suffix(b"\r\n") {
+            part = stripped;
+        }
+        if !part.is_empty() {
+            parts.push(part);
+        }
+    }
+    parts
+}
+
+/// Parse a fully-buffered `multipart/form-data` body into plain text fields
+/// and uploaded files. File parts over `INLINE_MAX_SIZE` are spooled to a
+/// temp file (exposed via `PyUploadFile::path`); smaller ones are kept
+/// in-memory (exposed via `PyUploadFile::content`).
+pub(crate) fn parse_multipart_body(
+    body: &[u8],
+    boundary: &str,
+) -> Result<(HashMap<String, String>, Vec<PyUploadFile>), String> {
+    let mut fields = HashMap::new();
+    let mut files = Vec::new();
+
+    for raw_part in split_parts(body, boundary) {
+        let Some(&header_end) = find_all(raw_part, b"\r\n\r\n").first() else {
+            continue;
+        };
+        let header_block = &raw_part[..header_end];
+        let part_body = &raw_part[header_end + 4..];
+
+        let Some((name, filename, content_type)) = parse_header_block(header_block) else {
+            continue;
+        };
+
+        match filename {
+            Some(filename) => {
+                let size = part_body.len() as u64;
+                let upload_file = if part_body.len() > INLINE_MAX_SIZE {
+                    let upload_dir = std::env::temp_dir().join("rupy-uploads");
+                    std::fs::create_dir_all(&upload_dir)
+                        .map_err(|e| format!("Failed to create upload directory: {}", e))?;
+
+                    let mut temp_file = NamedTempFile::new_in(&upload_dir)
+                        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+                    temp_file
+                        .write_all(part_body)
+                        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+
+                    let persisted_path = temp_file
+                        .into_temp_path()
+                        .keep()
+                        .map_err(|e| format!("Failed to persist temp file: {}", e))?;
+
+                    PyUploadFile::from_parts(
+                        filename,
+                        content_type,
+                        size,
+                        persisted_path.to_string_lossy().to_string(),
+                        Vec::new(),
+                    )
+                } else {
+                    PyUploadFile::from_parts(
+                        filename,
+                        content_type,
+                        size,
+                        String::new(),
+                        part_body.to_vec(),
+                    )
+                };
+                files.push(upload_file);
+            }
+            None => {
+                fields.insert(name, String::from_utf8_lossy(part_body).to_string());
+            }
+        }
+    }
+
+    Ok((fields, files))
+}
@@ -1,5 +1,7 @@
+use crate::auth::Identity;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::types::{PyDict, PyList};
 use std::collections::HashMap;
 use percent_encoding::percent_decode_str;
 
@@ -14,6 +16,66 @@ fn decode_query_value(s: &str) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Tokenize an `application/x-www-form-urlencoded` string into ordered,
+/// decoded key/value pairs. Shared by query-string and form-body parsing
+/// since both use the same `+`-as-space, percent-encoded `key=value&...` format.
+pub(crate) fn parse_encoded_pairs(s: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    for param in s.split('&') {
+        if param.is_empty() {
+            continue;
+        }
+
+        if let Some(eq_pos) = param.find('=') {
+            let key = &param[..eq_pos];
+            let value = &param[eq_pos + 1..];
+            if let (Some(decoded_key), Some(decoded_value)) =
+                (decode_query_value(key), decode_query_value(value))
+            {
+                pairs.push((decoded_key, decoded_value));
+            }
+        } else if let Some(decoded_key) = decode_query_value(param) {
+            pairs.push((decoded_key, String::new()));
+        }
+    }
+    pairs
+}
+
+/// Convert a parsed `serde_json::Value` into the equivalent Python object
+pub(crate) fn json_value_to_py(py: Python, value: &serde_json::Value) -> PyResult<PyObject> {
+    use pyo3::IntoPyObjectExt;
+
+    match value {
+        serde_json::Value::Null => Ok(py.None()),
+        serde_json::Value::Bool(b) => b.into_py_any(py),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into_py_any(py)
+            } else if let Some(f) = n.as_f64() {
+                f.into_py_any(py)
+            } else {
+                n.to_string().into_py_any(py)
+            }
+        }
+        serde_json::Value::String(s) => s.into_py_any(py),
+        serde_json::Value::Array(items) => {
+            let mut converted = Vec::with_capacity(items.len());
+            for item in items {
+                converted.push(json_value_to_py(py, item)?);
+            }
+            let list = PyList::new(py, converted)?;
+            list.into_py_any(py)
+        }
+        serde_json::Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, val) in map {
+                dict.set_item(key, json_value_to_py(py, val)?)?;
+            }
+            dict.into_py_any(py)
+        }
+    }
+}
+
 #[pyclass]
 #[derive(Clone)]
 pub struct PyRequest {
@@ -25,36 +87,104 @@ pub struct PyRequest {
     body: String,
     headers: HashMap<String, String>,
     cookies: HashMap<String, String>,
+    /// Undecoded body bytes, kept alongside the lossy `body` string so binary
+    /// payloads (e.g. `multipart/form-data`) survive intact.
+    raw_body: Vec<u8>,
+    /// The identity resolved by the registered authenticator, if any (see
+    /// `Rupy.set_authenticator`).
+    #[pyo3(get)]
+    identity: Option<Identity>,
 }
 
 impl PyRequest {
     pub(crate) fn from_parts(
         method: String,
         path: String,
-        body: String,
+        raw_body: Vec<u8>,
         headers: HashMap<String, String>,
         cookies: HashMap<String, String>,
     ) -> Self {
+        let body = String::from_utf8_lossy(&raw_body).to_string();
         PyRequest {
             method,
             path,
             body,
             headers,
             cookies,
+            raw_body,
+            identity: None,
+        }
+    }
+
+    /// Attach the identity resolved by the registered authenticator (used by
+    /// `handler_request` after a successful `auth::authenticate` call)
+    pub(crate) fn set_identity(&mut self, identity: Identity) {
+        self.identity = Some(identity);
+    }
+
+    /// Parse the body as JSON, if it is valid JSON (used by the `params`
+    /// validation subsystem to validate `location="body"` parameters)
+    pub(crate) fn parsed_json_body(&self) -> Option<serde_json::Value> {
+        serde_json::from_str(&self.body).ok()
+    }
+
+    /// Tokenize the query string into ordered, decoded key/value pairs.
+    ///
+    /// The single-value and multi-value getters (and the dict getters) build
+    /// on this so the query string is only split and percent-decoded once
+    /// per access; `get_query_keys` parses the query string on its own.
+    fn parse_query_pairs(&self) -> Vec<(String, String)> {
+        match self.path.find('?') {
+            Some(query_start) => parse_encoded_pairs(&self.path[query_start + 1..]),
+            None => Vec::new(),
         }
     }
+
+    /// Find a request header case-insensitively
+    fn find_header(&self, name: &str) -> Option<&String> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+
+    /// Read the Bearer token from the Authorization header, if any
+    pub(crate) fn auth_token_value(&self) -> Option<String> {
+        self.find_header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(|s| s.to_string())
+    }
+
+    /// Set the Authorization header to a Bearer token (used by `Session::apply_to_request`)
+    pub(crate) fn set_auth_token_header(&mut self, token: &str) {
+        self.headers
+            .insert("authorization".to_string(), format!("Bearer {}", token));
+    }
+
+    /// Snapshot the request's cookies (used by `Session::capture_from_request`)
+    pub(crate) fn cookies_snapshot(&self) -> HashMap<String, String> {
+        self.cookies.clone()
+    }
+
+    /// Set a cookie value (used by `Session::apply_to_request`)
+    pub(crate) fn set_cookie_value(&mut self, name: String, value: String) {
+        self.cookies.insert(name, value);
+    }
 }
 
 #[pymethods]
 impl PyRequest {
     #[new]
     fn new(method: String, path: String, body: String) -> Self {
+        let raw_body = body.clone().into_bytes();
         PyRequest {
             method,
             path,
             body,
             headers: HashMap::new(),
             cookies: HashMap::new(),
+            raw_body,
+            identity: None,
         }
     }
 
@@ -97,15 +227,23 @@ impl PyRequest {
         Ok(dict.into())
     }
 
+    /// Get a signed cookie value by name, verifying its HMAC signature
+    ///
+    /// Returns `None` if the cookie is absent, was not signed with
+    /// `PyResponse.set_signed_cookie`, or fails verification (tampered or
+    /// signed with a different secret key) - an invalid signature behaves
+    /// exactly like a missing cookie.
+    fn get_signed_cookie(&self, _py: Python, name: String) -> PyResult<Option<String>> {
+        match self.cookies.get(&name) {
+            Some(signed_value) => Ok(crate::cookie::verify(&name, signed_value)),
+            None => Ok(None),
+        }
+    }
+
     /// Get the auth token from the Authorization header (Bearer token)
     #[getter]
     fn auth_token(&self, _py: Python) -> PyResult<Option<String>> {
-        let auth_header = self
-            .headers
-            .iter()
-            .find(|(k, _)| k.eq_ignore_ascii_case("authorization"))
-            .map(|(_, v)| v);
-        if let Some(auth_header) = auth_header {
+        if let Some(auth_header) = self.find_header("authorization") {
             if let Some(token) = auth_header.strip_prefix("Bearer ") {
                 return Ok(Some(token.to_string()));
             }
@@ -128,6 +266,21 @@ impl PyRequest {
         Ok(())
     }
 
+    /// Validate this request's Bearer token against the settings configured
+    /// via `Rupy.configure_jwt` and return its decoded claims as a dict.
+    ///
+    /// Raises if JWT verification isn't configured, the Authorization header
+    /// has no Bearer token, or the token fails signature/claim validation.
+    #[getter]
+    fn jwt_claims(&self, py: Python) -> PyResult<PyObject> {
+        let token = self
+            .auth_token_value()
+            .ok_or_else(|| PyValueError::new_err("No Bearer token in the Authorization header"))?;
+
+        let claims = crate::jwt::validate(&token).map_err(PyValueError::new_err)?;
+        json_value_to_py(py, &claims)
+    }
+
     /// Get query string keys from the path
     /// 
     /// Returns a list of query parameter keys, URL-decoded.
@@ -200,35 +353,31 @@ impl PyRequest {
     /// For path `/search?q=rust+programming&page=2`, 
     /// `get_query_param("q")` returns `Some("rust programming")`
     fn get_query_param(&self, _py: Python, key: String) -> PyResult<Option<String>> {
-        if let Some(query_start) = self.path.find('?') {
-            let query_string = &self.path[query_start + 1..];
-            let mut result = None;
-            
-            for param in query_string.split('&') {
-                if let Some(eq_pos) = param.find('=') {
-                    let param_key = &param[..eq_pos];
-                    
-                    // URL decode the key for comparison
-                    if let Some(decoded_key) = decode_query_value(param_key) {
-                        if decoded_key == key {
-                            let value = &param[eq_pos + 1..];
-                            // URL decode the value
-                            result = decode_query_value(value);
-                        }
-                    }
-                } else if !param.is_empty() {
-                    // Handle parameters without values (e.g., ?flag)
-                    if let Some(decoded_key) = decode_query_value(param) {
-                        if decoded_key == key {
-                            result = Some(String::new());
-                        }
-                    }
-                }
+        let mut result = None;
+        for (param_key, value) in self.parse_query_pairs() {
+            if param_key == key {
+                result = Some(value);
             }
-            Ok(result)
-        } else {
-            Ok(None)
         }
+        Ok(result)
+    }
+
+    /// Get every value for a repeated query parameter, in order
+    ///
+    /// Unlike `get_query_param`, which only returns the last occurrence,
+    /// this returns all decoded values for `key` in the order they appear
+    /// in the query string (e.g. `?tag=a&tag=b&tag=c` -> `["a", "b", "c"]`).
+    /// Flag parameters (without values) contribute an empty string.
+    ///
+    /// # Returns
+    /// * `Vec<String>` - All decoded values for `key`, empty if absent
+    fn get_query_param_all(&self, _py: Python, key: String) -> PyResult<Vec<String>> {
+        Ok(self
+            .parse_query_pairs()
+            .into_iter()
+            .filter(|(param_key, _)| param_key == &key)
+            .map(|(_, value)| value)
+            .collect())
     }
 
     /// Get all query parameters as a dictionary
@@ -246,34 +395,107 @@ impl PyRequest {
     #[getter]
     fn query_params(&self, py: Python) -> PyResult<Py<PyDict>> {
         let dict = PyDict::new(py);
-        if let Some(query_start) = self.path.find('?') {
-            let query_string = &self.path[query_start + 1..];
-            for param in query_string.split('&') {
-                if param.is_empty() {
-                    continue;
+        for (key, value) in self.parse_query_pairs() {
+            dict.set_item(&key, &value)?;
+        }
+        Ok(dict.into())
+    }
+
+    /// Get all query parameters as a dictionary of lists
+    ///
+    /// Like `query_params`, but every key maps to a Python list holding
+    /// *all* of its decoded values in order, so repeated keys (e.g.
+    /// `?tag=a&tag=b`) don't lose data. Keys appear in first-seen order.
+    ///
+    /// # Returns
+    /// * `Py<PyDict>` - Dictionary mapping decoded keys to lists of values
+    ///
+    /// # Example
+    /// For path `/search?tag=a&tag=b&tag=c`, returns `{"tag": ["a", "b", "c"]}`
+    #[getter]
+    fn query_params_multi(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        for (key, value) in self.parse_query_pairs() {
+            match dict.get_item(&key)? {
+                Some(existing) => {
+                    let list = existing.downcast::<pyo3::types::PyList>()?;
+                    list.append(value)?;
                 }
-                
-                if let Some(eq_pos) = param.find('=') {
-                    let key = &param[..eq_pos];
-                    let value = &param[eq_pos + 1..];
-                    
-                    // URL decode both key and value
-                    if let (Some(decoded_key), Some(decoded_value)) = (
-                        decode_query_value(key),
-                        decode_query_value(value),
-                    ) {
-                        dict.set_item(&decoded_key, &decoded_value)?;
-                    }
-                } else {
-                    // Handle parameters without values (e.g., ?flag)
-                    if let Some(decoded_key) = decode_query_value(param) {
-                        dict.set_item(&decoded_key, "")?;
-                    }
+                None => {
+                    let list = pyo3::types::PyList::new(py, [value])?;
+                    dict.set_item(&key, list)?;
                 }
             }
         }
         Ok(dict.into())
     }
+
+    /// Parse the request body as JSON
+    ///
+    /// Decodes `body` as JSON and returns the equivalent Python object (a
+    /// `dict` or `list` for JSON objects/arrays, otherwise the corresponding
+    /// scalar). The `Content-Type` header is not checked: the body is parsed
+    /// as JSON whatever type it declares. Raises `ValueError` if the body
+    /// is not valid JSON, so handlers don't each need `json.loads`.
+    fn json(&self, py: Python) -> PyResult<PyObject> {
+        let value: serde_json::Value = serde_json::from_str(&self.body)
+            .map_err(|e| PyValueError::new_err(format!("Invalid JSON body: {}", e)))?;
+        json_value_to_py(py, &value)
+    }
+
+    /// Parse the request body as `application/x-www-form-urlencoded`
+    ///
+    /// Uses the same `+`-as-space, percent-decoding logic as the query
+    /// string parser, so `POST` form submissions and query strings behave
+    /// identically. Repeated field names keep only the last value, matching
+    /// `query_params`.
+    ///
+    /// # Returns
+    /// * `Py<PyDict>` - Dictionary of decoded form fields
+    fn form(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        for (key, value) in parse_encoded_pairs(&self.body) {
+            dict.set_item(&key, &value)?;
+        }
+        Ok(dict.into())
+    }
+
+    /// Parse a `multipart/form-data` body into fields and uploaded files
+    ///
+    /// Returns a dict with two keys: `"fields"`, a dict of the plain text
+    /// form fields, and `"files"`, a list of `PyUploadFile` objects (one per
+    /// uploaded file part). Small files are kept in memory (`file.content`);
+    /// large ones are spooled to a temp file (`file.path`). Raises
+    /// `ValueError` if the `Content-Type` header has no multipart boundary
+    /// or the body is malformed.
+    fn multipart(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let content_type = self
+            .find_header("content-type")
+            .cloned()
+            .unwrap_or_default();
+
+        let boundary = crate::multipart::extract_boundary(&content_type).ok_or_else(|| {
+            PyValueError::new_err("Missing multipart boundary in Content-Type header")
+        })?;
+
+        let (fields, files) = crate::multipart::parse_multipart_body(&self.raw_body, &boundary)
+            .map_err(PyValueError::new_err)?;
+
+        let fields_dict = PyDict::new(py);
+        for (key, value) in fields {
+            fields_dict.set_item(key, value)?;
+        }
+
+        let files_list = PyList::empty(py);
+        for file in files {
+            files_list.append(Py::new(py, file)?)?;
+        }
+
+        let result = PyDict::new(py);
+        result.set_item("fields", fields_dict)?;
+        result.set_item("files", files_list)?;
+        Ok(result.into())
+    }
 }
 
 pub fn parse_cookies(cookie_header: &str) -> HashMap<String, String> {
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A route handler implemented as a Rhai script rather than a Python
+/// callable, for simple redirect/transform endpoints that don't need the
+/// GIL. The compiled `AST` is cached after the first request so the script
+/// is only parsed once per route.
+#[derive(Clone)]
+pub struct RhaiHandler {
+    source: ScriptSource,
+    compiled: Arc<Mutex<Option<Arc<rhai::AST>>>>,
+}
+
+#[derive(Clone)]
+enum ScriptSource {
+    Inline(String),
+    File(String),
+}
+
+/// The request data exposed to a Rhai script as the `request` variable,
+/// mirroring the fields a Python handler gets off `PyRequest`.
+#[derive(serde::Serialize)]
+pub struct RhaiRequest {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl RhaiHandler {
+    pub fn inline(script: String) -> Self {
+        RhaiHandler {
+            source: ScriptSource::Inline(script),
+            compiled: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn file(path: String) -> Self {
+        RhaiHandler {
+            source: ScriptSource::File(path),
+            compiled: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Evaluate this handler's script against one request, returning its
+    /// result as JSON. A script that returns a string yields
+    /// `serde_json::Value::String`; a script returning a Rhai object map
+    /// yields a JSON object, handled by the caller exactly like a Python
+    /// template/dict handler's return value.
+    pub fn invoke(&self, request: &RhaiRequest) -> Result<serde_json::Value, String> {
+        let ast = self.compiled_ast()?;
+        let engine = build_engine();
+
+        let request_dynamic = rhai::serde::to_dynamic(request)
+            .map_err(|e| format!("Failed to convert request to a Rhai value: {}", e))?;
+        let mut scope = rhai::Scope::new();
+        scope.push("request", request_dynamic);
+
+        let result: rhai::Dynamic = engine
+            .eval_ast_with_scope(&mut scope, &ast)
+            .map_err(|e| format!("Rhai script error: {}", e))?;
+
+        rhai::serde::from_dynamic(&result)
+            .map_err(|e| format!("Failed to convert Rhai result to JSON: {}", e))
+    }
+
+    fn compiled_ast(&self) -> Result<Arc<rhai::AST>, String> {
+        let mut compiled = self.compiled.lock().unwrap();
+        if let Some(ast) = compiled.as_ref() {
+            return Ok(ast.clone());
+        }
+
+        let engine = build_engine();
+        let ast = match &self.source {
+            ScriptSource::Inline(script) => engine.compile(script),
+            ScriptSource::File(path) => engine.compile_file(path.into()),
+        }
+        .map_err(|e| format!("Failed to compile Rhai script: {}", e))?;
+
+        let ast = Arc::new(ast);
+        *compiled = Some(ast.clone());
+        Ok(ast)
+    }
+}
+
+fn build_engine() -> rhai::Engine {
+    rhai::Engine::new()
+}
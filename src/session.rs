@@ -0,0 +1,111 @@
+use crate::cookie::Cookie;
+use crate::request::PyRequest;
+use pyo3::exceptions::PyIOError;
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Bumped whenever the on-disk session format changes in an incompatible way
+const SESSION_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone)]
+struct SessionMeta {
+    version: u32,
+}
+
+/// A persisted session: auth state plus a cookie jar, serializable to and
+/// from a JSON file. Modeled on the session-file format used by HTTP
+/// clients like xh, including a small versioned `__meta__` envelope so
+/// future format changes can be detected and migrated.
+#[pyclass]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Session {
+    #[pyo3(get, set)]
+    id: String,
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    auth_token: Option<String>,
+    #[serde(default)]
+    cookies: Vec<Cookie>,
+    #[serde(rename = "__meta__")]
+    meta: SessionMeta,
+}
+
+#[pymethods]
+impl Session {
+    #[new]
+    fn new(id: String) -> Self {
+        Session {
+            id,
+            auth_token: None,
+            cookies: Vec::new(),
+            meta: SessionMeta {
+                version: SESSION_FORMAT_VERSION,
+            },
+        }
+    }
+
+    /// Load a session previously written by `save`
+    #[staticmethod]
+    fn load(path: String) -> PyResult<Session> {
+        let contents = fs::read_to_string(&path).map_err(|e| {
+            PyIOError::new_err(format!("Failed to read session file '{}': {}", path, e))
+        })?;
+        serde_json::from_str(&contents).map_err(|e| {
+            PyIOError::new_err(format!("Failed to parse session file '{}': {}", path, e))
+        })
+    }
+
+    /// Save this session to disk as JSON
+    fn save(&self, path: String) -> PyResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| PyIOError::new_err(format!("Failed to serialize session: {}", e)))?;
+        fs::write(&path, json).map_err(|e| {
+            PyIOError::new_err(format!("Failed to write session file '{}': {}", path, e))
+        })
+    }
+
+    /// Get the cookies captured in this session
+    fn get_cookies(&self) -> Vec<Cookie> {
+        self.cookies.clone()
+    }
+
+    /// Replace the session's cookie jar
+    fn set_cookies(&mut self, cookies: Vec<Cookie>) {
+        self.cookies = cookies;
+    }
+
+    /// Apply this session's auth token and cookies onto a `PyRequest`,
+    /// returning the updated request so it can be sent with the session's
+    /// identity attached.
+    fn apply_to_request(&self, mut request: PyRequest) -> PyRequest {
+        if let Some(token) = &self.auth_token {
+            request.set_auth_token_header(token);
+        }
+        for cookie in &self.cookies {
+            request.set_cookie_value(cookie.name.clone(), cookie.value.clone());
+        }
+        request
+    }
+
+    /// Capture the auth token and cookies from an incoming `PyRequest` into
+    /// this session, overwriting whatever was previously stored.
+    fn capture_from_request(&mut self, request: &PyRequest) {
+        self.auth_token = request.auth_token_value();
+        self.cookies = request
+            .cookies_snapshot()
+            .into_iter()
+            .map(|(name, value)| Cookie {
+                name,
+                value,
+                max_age: None,
+                expires: None,
+                path: None,
+                domain: None,
+                secure: false,
+                http_only: false,
+                same_site: None,
+            })
+            .collect();
+    }
+}
@@ -0,0 +1,252 @@
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+/// Where an upload route's files are written.
+///
+/// `route_upload` picks `ObjectStore` when a bucket is configured and
+/// `LocalFs` otherwise, so existing callers that only ever set `upload_dir`
+/// keep writing to disk exactly as before.
+#[derive(Clone)]
+pub enum UploadBackend {
+    LocalFs {
+        upload_dir: String,
+    },
+    ObjectStore(ObjectStoreConfig),
+}
+
+/// Connection details for an S3-compatible object store.
+#[derive(Clone)]
+pub struct ObjectStoreConfig {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// Use `https://endpoint/bucket/key` addressing instead of
+    /// `https://bucket.endpoint/key` (needed for most non-AWS S3-compatible
+    /// services, e.g. MinIO).
+    pub path_style: bool,
+}
+
+/// S3 requires every part but the last to be at least 5 MiB.
+const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// An in-progress upload write, abstracting over where the bytes end up so
+/// `process_multipart_upload`'s size-limit/content-sniffing logic doesn't
+/// need to know which backend is in use.
+pub enum UploadSink {
+    LocalFs(NamedTempFile),
+    ObjectStore(ObjectStoreUpload),
+}
+
+impl UploadSink {
+    /// Open a new sink for one uploaded field.
+    pub async fn new(backend: &UploadBackend) -> Result<Self, String> {
+        match backend {
+            UploadBackend::LocalFs { upload_dir } => {
+                std::fs::create_dir_all(upload_dir)
+                    .map_err(|e| format!("Failed to create upload directory: {}", e))?;
+                let temp_file = NamedTempFile::new_in(upload_dir)
+                    .map_err(|e| format!("Failed to create temp file: {}", e))?;
+                Ok(UploadSink::LocalFs(temp_file))
+            }
+            UploadBackend::ObjectStore(config) => {
+                Ok(UploadSink::ObjectStore(ObjectStoreUpload::start(config).await?))
+            }
+        }
+    }
+
+    /// Write the next chunk of the field's bytes through to the backend.
+    pub async fn write_chunk(&mut self, chunk: &[u8]) -> Result<(), String> {
+        match self {
+            UploadSink::LocalFs(temp_file) => temp_file
+                .write_all(chunk)
+                .map_err(|e| format!("Failed to write to temp file: {}", e)),
+            UploadSink::ObjectStore(upload) => upload.write_chunk(chunk).await,
+        }
+    }
+
+    /// Finalize the upload, returning the path (`LocalFs`) or object
+    /// key/URL (`ObjectStore`) exposed to Python as `PyUploadFile.path`.
+    pub async fn finish(self, filename: &str) -> Result<String, String> {
+        match self {
+            UploadSink::LocalFs(mut temp_file) => {
+                temp_file
+                    .flush()
+                    .map_err(|e| format!("Failed to flush temp file: {}", e))?;
+                let persisted_path = temp_file
+                    .into_temp_path()
+                    .keep()
+                    .map_err(|e| format!("Failed to persist temp file: {}", e))?;
+                Ok(persisted_path.to_string_lossy().to_string())
+            }
+            UploadSink::ObjectStore(upload) => upload.finish(filename).await,
+        }
+    }
+}
+
+/// Drives an S3 multipart upload (`CreateMultipartUpload` /
+/// `UploadPart` / `CompleteMultipartUpload`), buffering chunks until there's
+/// enough for a part so each part (but the last) meets S3's 5 MiB minimum.
+pub struct ObjectStoreUpload {
+    client: aws_sdk_s3::Client,
+    config: ObjectStoreConfig,
+    key: String,
+    upload_id: String,
+    part_number: i32,
+    completed_parts: Vec<aws_sdk_s3::types::CompletedPart>,
+    buffer: Vec<u8>,
+}
+
+impl ObjectStoreUpload {
+    async fn start(config: &ObjectStoreConfig) -> Result<Self, String> {
+        let client = build_client(config);
+        // Temporary key; renamed to its final, filename-derived form by
+        // `finish` once the field's filename is known.
+        let key = format!(
+            "uploads/{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_err(|e| format!("System clock error: {}", e))?
+                .as_nanos()
+        );
+
+        let create = client
+            .create_multipart_upload()
+            .bucket(&config.bucket)
+            .key(&key)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to start S3 multipart upload: {}", e))?;
+
+        let upload_id = create
+            .upload_id()
+            .ok_or("S3 did not return an upload id")?
+            .to_string();
+
+        Ok(ObjectStoreUpload {
+            client,
+            config: config.clone(),
+            key,
+            upload_id,
+            part_number: 1,
+            completed_parts: Vec::new(),
+            buffer: Vec::with_capacity(MIN_PART_SIZE),
+        })
+    }
+
+    async fn write_chunk(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.buffer.extend_from_slice(chunk);
+        if self.buffer.len() >= MIN_PART_SIZE {
+            self.flush_part().await?;
+        }
+        Ok(())
+    }
+
+    async fn flush_part(&mut self) -> Result<(), String> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let body = std::mem::take(&mut self.buffer);
+        let part_number = self.part_number;
+
+        let result = self
+            .client
+            .upload_part()
+            .bucket(&self.config.bucket)
+            .key(&self.key)
+            .upload_id(&self.upload_id)
+            .part_number(part_number)
+            .body(aws_sdk_s3::primitives::ByteStream::from(body))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to upload S3 part {}: {}", part_number, e))?;
+
+        self.completed_parts.push(
+            aws_sdk_s3::types::CompletedPart::builder()
+                .part_number(part_number)
+                .set_e_tag(result.e_tag().map(|s| s.to_string()))
+                .build(),
+        );
+        self.part_number += 1;
+
+        Ok(())
+    }
+
+    async fn finish(mut self, filename: &str) -> Result<String, String> {
+        self.flush_part().await?;
+
+        let final_key = format!("{}-{}", self.key, filename);
+        let completed = aws_sdk_s3::types::CompletedMultipartUpload::builder()
+            .set_parts(Some(self.completed_parts))
+            .build();
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.config.bucket)
+            .key(&self.key)
+            .upload_id(&self.upload_id)
+            .multipart_upload(completed)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to complete S3 multipart upload: {}", e))?;
+
+        // The object was written under the temporary key; S3 has no rename,
+        // so copy it to the filename-derived key and drop the original.
+        self.client
+            .copy_object()
+            .bucket(&self.config.bucket)
+            .copy_source(format!("{}/{}", self.config.bucket, self.key))
+            .key(&final_key)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to finalize S3 object key: {}", e))?;
+        let _ = self
+            .client
+            .delete_object()
+            .bucket(&self.config.bucket)
+            .key(&self.key)
+            .send()
+            .await;
+
+        Ok(object_url(&self.config, &final_key))
+    }
+}
+
+fn build_client(config: &ObjectStoreConfig) -> aws_sdk_s3::Client {
+    let credentials = aws_sdk_s3::config::Credentials::new(
+        &config.access_key,
+        &config.secret_key,
+        None,
+        None,
+        "rupy",
+    );
+    let s3_config = aws_sdk_s3::config::Builder::new()
+        .region(aws_sdk_s3::config::Region::new(config.region.clone()))
+        .endpoint_url(&config.endpoint)
+        .credentials_provider(credentials)
+        .force_path_style(config.path_style)
+        .behavior_version(aws_sdk_s3::config::BehaviorVersion::latest())
+        .build();
+    aws_sdk_s3::Client::from_conf(s3_config)
+}
+
+/// Build the object's public-ish URL for `PyUploadFile.path`, in whichever
+/// addressing style the store is configured for.
+fn object_url(config: &ObjectStoreConfig, key: &str) -> String {
+    if config.path_style {
+        format!("{}/{}/{}", config.endpoint.trim_end_matches('/'), config.bucket, key)
+    } else {
+        let endpoint = config
+            .endpoint
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        let scheme = if config.endpoint.starts_with("http://") {
+            "http"
+        } else {
+            "https"
+        };
+        format!("{}://{}.{}/{}", scheme, config.bucket, endpoint, key)
+    }
+}
@@ -1,75 +1,349 @@
-use handlebars::Handlebars;
+use handlebars::{Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderErrorReason};
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
-use std::path::PathBuf;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
-#[derive(Clone)]
-pub struct TemplateConfig {
-    pub template_dir: String,
-    pub template_dirs: Vec<String>,
+/// Bridges a Python callable into a Handlebars helper, so
+/// `Rupy.register_template_helper` lets handlers extend `{{helper arg}}`
+/// expressions without touching Rust.
+struct PyHelper {
+    callback: Py<PyAny>,
 }
 
+impl HelperDef for PyHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _r: &'reg Handlebars<'reg>,
+        _ctx: &'rc Context,
+        _rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let rendered = Python::attach(|py| -> PyResult<String> {
+            let mut args = Vec::with_capacity(h.params().len());
+            for param in h.params() {
+                args.push(crate::request::json_value_to_py(py, param.value())?);
+            }
+            let args = PyTuple::new(py, args)?;
+            let result = self.callback.call1(py, args)?;
+            result.extract::<String>(py)
+        })
+        .map_err(|e| RenderErrorReason::Other(format!("Python helper error: {}", e)))?;
+
+        out.write(&rendered)?;
+        Ok(())
+    }
+}
+
+/// A persistent, cached Handlebars registry.
+///
+/// Every `.hbs` file under the configured `template_dirs` is registered
+/// once, keyed by its path relative to its directory with the extension
+/// stripped (e.g. `partials/header.hbs` -> `partials/header`), so templates
+/// can include one another via `{{> partials/header}}` and share layouts.
+/// Re-renders of an unchanged directory list skip disk I/O and re-parsing
+/// entirely.
+///
+/// Opt into `set_dev_mode(true)` to trade that zero-IO guarantee for live
+/// reload: every render then `stat()`s each `.hbs` file and re-registers
+/// only the ones whose mtime moved, so editing a template takes effect on
+/// the next request without restarting the app.
+pub struct TemplateEngine {
+    handlebars: Mutex<Handlebars<'static>>,
+    registered_dirs: Mutex<Vec<String>>,
+    dev_mode: AtomicBool,
+    file_mtimes: Mutex<HashMap<String, SystemTime>>,
+}
+
+impl TemplateEngine {
+    pub fn new() -> Self {
+        TemplateEngine {
+            handlebars: Mutex::new(Handlebars::new()),
+            registered_dirs: Mutex::new(Vec::new()),
+            dev_mode: AtomicBool::new(false),
+            file_mtimes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Enable or disable mtime-based hot reload.
+    pub fn set_dev_mode(&self, enabled: bool) {
+        self.dev_mode.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Re-scan `template_dirs` and register templates as needed.
+    ///
+    /// In the default mode, the whole registry is rebuilt once and skipped
+    /// on every render after, unless `template_dirs` itself changes. In dev
+    /// mode, every call re-stats each `.hbs` file and re-registers only the
+    /// ones that are new or whose mtime moved since the last scan.
+    fn ensure_registered(&self, template_dirs: &[String]) -> Result<(), String> {
+        if !self.dev_mode.load(Ordering::Relaxed) {
+            {
+                let registered = self.registered_dirs.lock().unwrap();
+                if registered.as_slice() == template_dirs {
+                    return Ok(());
+                }
+            }
+
+            // Clear only the templates, so helpers added via `register_helper`
+            // stay registered.
+            let mut handlebars = self.handlebars.lock().unwrap();
+            handlebars.clear_templates();
+            for dir in template_dirs {
+                let root = Path::new(dir);
+                if root.is_dir() {
+                    register_dir(&mut handlebars, root, root)?;
+                }
+            }
+            drop(handlebars);
+
+            *self.registered_dirs.lock().unwrap() = template_dirs.to_vec();
+            return Ok(());
+        }
+
+        let mut current_files = Vec::new();
+        for dir in template_dirs {
+            let root = Path::new(dir);
+            if root.is_dir() {
+                collect_hbs_files(root, root, &mut current_files)?;
+            }
+        }
+
+        let mut mtimes = self.file_mtimes.lock().unwrap();
+        let mut seen_names = HashSet::new();
+        let mut to_register = Vec::new();
+        for (name, path) in &current_files {
+            seen_names.insert(name.clone());
+            let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+            if mtime != mtimes.get(name).copied() {
+                to_register.push((name.clone(), path.clone()));
+                match mtime {
+                    Some(mtime) => {
+                        mtimes.insert(name.clone(), mtime);
+                    }
+                    None => {
+                        mtimes.remove(name);
+                    }
+                }
+            }
+        }
+        let removed: Vec<String> = mtimes
+            .keys()
+            .filter(|name| !seen_names.contains(*name))
+            .cloned()
+            .collect();
+        for name in &removed {
+            mtimes.remove(name);
+        }
+        drop(mtimes);
+
+        if to_register.is_empty() && removed.is_empty() {
+            return Ok(());
+        }
+
+        let mut handlebars = self.handlebars.lock().unwrap();
+        for name in &removed {
+            handlebars.unregister_template(name);
+        }
+        for (name, path) in &to_register {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read template '{}': {}", path.display(), e))?;
+            handlebars
+                .register_template_string(name, content)
+                .map_err(|e| format!("Failed to parse template '{}': {}", path.display(), e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Render `template_name` (registered name, `.hbs` suffix optional)
+    /// against `context`, re-scanning `template_dirs` first if they changed
+    /// since the last render.
+    pub fn render(
+        &self,
+        template_dirs: &[String],
+        template_name: &str,
+        context: &serde_json::Value,
+    ) -> Result<String, String> {
+        self.ensure_registered(template_dirs)?;
+
+        let name = template_name.trim_end_matches(".hbs");
+        let handlebars = self.handlebars.lock().unwrap();
+        if !handlebars.has_template(name) {
+            return Err(format!(
+                "Template '{}' not found under directories: {}",
+                template_name,
+                template_dirs.join(", ")
+            ));
+        }
+
+        handlebars
+            .render(name, context)
+            .map_err(|e| format!("Failed to render template: {}", e))
+    }
+
+    /// Register a Python callable as a named helper, available to every
+    /// template rendered by this engine.
+    pub fn register_helper(&self, name: &str, callback: Py<PyAny>) {
+        self.handlebars
+            .lock()
+            .unwrap()
+            .register_helper(name, Box::new(PyHelper { callback }));
+    }
+}
+
+impl Default for TemplateEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recursively register every `.hbs` file under `dir` as a named template,
+/// keyed by its path relative to `root` with the extension stripped.
+fn register_dir(handlebars: &mut Handlebars, root: &Path, dir: &Path) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read template directory '{}': {}", dir.display(), e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let path = entry.path();
+
+        if path.is_dir() {
+            register_dir(handlebars, root, &path)?;
+            continue;
+        }
+
+        if path.extension().and_then(|ext| ext.to_str()) != Some("hbs") {
+            continue;
+        }
+
+        let relative = path.strip_prefix(root).unwrap_or(&path).with_extension("");
+        let name = relative
+            .to_string_lossy()
+            .replace(std::path::MAIN_SEPARATOR, "/");
+
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read template '{}': {}", path.display(), e))?;
+
+        handlebars
+            .register_template_string(&name, content)
+            .map_err(|e| format!("Failed to parse template '{}': {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
+
+/// Recursively collect every `.hbs` file under `dir` as a `(name, path)`
+/// pair, using the same relative-name convention as `register_dir`. Used by
+/// dev-mode hot reload, which needs each file's path to `stat()` it.
+fn collect_hbs_files(
+    root: &Path,
+    dir: &Path,
+    out: &mut Vec<(String, PathBuf)>,
+) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read template directory '{}': {}", dir.display(), e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect_hbs_files(root, &path, out)?;
+            continue;
+        }
+
+        if path.extension().and_then(|ext| ext.to_str()) != Some("hbs") {
+            continue;
+        }
+
+        let relative = path.strip_prefix(root).unwrap_or(&path).with_extension("");
+        let name = relative
+            .to_string_lossy()
+            .replace(std::path::MAIN_SEPARATOR, "/");
+
+        out.push((name, path));
+    }
+
+    Ok(())
+}
+
+/// Render a template using a throwaway `TemplateEngine` (kept for callers
+/// that render once and don't hold a persistent engine instance).
 pub fn render_template_with_dirs(
     template_dirs: &[String],
     template_name: &str,
     context: &serde_json::Value,
 ) -> Result<String, String> {
-    let mut handlebars = Handlebars::new();
+    TemplateEngine::new().render(template_dirs, template_name, context)
+}
 
-    let mut template_content = None;
-    let mut tried_paths = Vec::new();
+/// Render a template using Handlebars (backward compatibility)
+pub fn render_template(
+    template_dir: &str,
+    template_name: &str,
+    context: &serde_json::Value,
+) -> Result<String, String> {
+    render_template_with_dirs(&[template_dir.to_string()], template_name, context)
+}
 
-    for template_dir in template_dirs {
-        let template_path = PathBuf::from(template_dir).join(template_name);
-        tried_paths.push(template_path.display().to_string());
+/// Convert a Python dict to a JSON value, recursing into nested dicts and
+/// lists so handlers can pass arbitrarily nested context objects to templates.
+pub fn py_dict_to_json(py: Python, py_dict: &Py<PyDict>) -> PyResult<serde_json::Value> {
+    py_any_to_json(py_dict.bind(py).as_any())
+}
 
-        if let Ok(content) = std::fs::read_to_string(&template_path) {
-            template_content = Some(content);
-            break;
+/// Convert an arbitrary Python value to a JSON value, recursing into dicts,
+/// lists and tuples. Anything else that isn't a recognized scalar falls back
+/// to its `str()` representation, same as the non-recursive scalar cases did.
+pub(crate) fn py_any_to_json(value: &Bound<PyAny>) -> PyResult<serde_json::Value> {
+    if let Ok(dict) = value.cast::<PyDict>() {
+        let mut map = serde_json::Map::new();
+        for (key, val) in dict.iter() {
+            let key_str = key.extract::<String>()?;
+            map.insert(key_str, py_any_to_json(&val)?);
         }
+        return Ok(serde_json::Value::Object(map));
     }
 
-    let template_content = template_content.ok_or_else(|| {
-        format!(
-            "Failed to read template file '{}'. Tried paths: {}",
-            template_name,
-            tried_paths.join(", ")
-        )
-    })?;
-
-    handlebars
-        .register_template_string("template", template_content)
-        .map_err(|e| format!("Failed to parse template: {}", e))?;
+    if let Ok(list) = value.cast::<PyList>() {
+        let mut items = Vec::with_capacity(list.len());
+        for item in list.iter() {
+            items.push(py_any_to_json(&item)?);
+        }
+        return Ok(serde_json::Value::Array(items));
+    }
 
-    handlebars
-        .render("template", context)
-        .map_err(|e| format!("Failed to render template: {}", e))
-}
+    if let Ok(tuple) = value.cast::<PyTuple>() {
+        let mut items = Vec::with_capacity(tuple.len());
+        for item in tuple.iter() {
+            items.push(py_any_to_json(&item)?);
+        }
+        return Ok(serde_json::Value::Array(items));
+    }
 
-pub fn py_dict_to_json(py: Python, py_dict: &Py<PyDict>) -> PyResult<serde_json::Value> {
-    let dict = py_dict.bind(py);
-    let mut context = serde_json::Map::new();
-
-    for (key, value) in dict.iter() {
-        let key_str = key.extract::<String>()?;
-        let json_value = if let Ok(s) = value.extract::<String>() {
-            serde_json::Value::String(s)
-        } else if let Ok(i) = value.extract::<i64>() {
-            serde_json::Value::Number(i.into())
-        } else if let Ok(f) = value.extract::<f64>() {
-            match serde_json::Number::from_f64(f) {
-                Some(n) => serde_json::Value::Number(n),
-                None => serde_json::Value::Null, // NaN/infinity -> null
-            }
-        } else if let Ok(b) = value.extract::<bool>() {
-            serde_json::Value::Bool(b)
-        } else if value.is_none() {
-            serde_json::Value::Null
-        } else {
-            serde_json::Value::String(value.to_string())
-        };
-        context.insert(key_str, json_value);
+    if let Ok(s) = value.extract::<String>() {
+        return Ok(serde_json::Value::String(s));
+    }
+    if let Ok(i) = value.extract::<i64>() {
+        return Ok(serde_json::Value::Number(i.into()));
+    }
+    if let Ok(f) = value.extract::<f64>() {
+        return Ok(match serde_json::Number::from_f64(f) {
+            Some(n) => serde_json::Value::Number(n),
+            None => serde_json::Value::Null, // NaN/infinity -> null
+        });
+    }
+    if let Ok(b) = value.extract::<bool>() {
+        return Ok(serde_json::Value::Bool(b));
+    }
+    if value.is_none() {
+        return Ok(serde_json::Value::Null);
     }
 
-    Ok(serde_json::Value::Object(context))
+    Ok(serde_json::Value::String(value.to_string()))
 }
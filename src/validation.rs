@@ -0,0 +1,258 @@
+use pyo3::prelude::*;
+use std::collections::HashMap;
+
+/// Declares a single request parameter a route expects, so `handler_request`
+/// can coerce and validate it before the handler ever runs instead of every
+/// handler re-parsing and re-checking its own inputs.
+#[pyclass]
+#[derive(Clone)]
+pub struct ParamSpec {
+    #[pyo3(get)]
+    name: String,
+    /// Where the raw value comes from: "path", "query", or "body"
+    location: String,
+    /// The target type: "int", "float", "bool", "str", or "uuid"
+    param_type: String,
+    required: bool,
+    default_json: Option<serde_json::Value>,
+    min: Option<f64>,
+    max: Option<f64>,
+    regex: Option<String>,
+}
+
+#[pymethods]
+impl ParamSpec {
+    #[new]
+    #[pyo3(signature = (name, location, param_type, required=true, default=None, min=None, max=None, regex=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        py: Python,
+        name: String,
+        location: String,
+        param_type: String,
+        required: bool,
+        default: Option<Py<PyAny>>,
+        min: Option<f64>,
+        max: Option<f64>,
+        regex: Option<String>,
+    ) -> PyResult<Self> {
+        let default_json = match default {
+            Some(obj) => Some(crate::template::py_any_to_json(obj.bind(py))?),
+            None => None,
+        };
+
+        Ok(ParamSpec {
+            name,
+            location,
+            param_type,
+            required,
+            default_json,
+            min,
+            max,
+            regex,
+        })
+    }
+}
+
+impl ParamSpec {
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn location(&self) -> &str {
+        &self.location
+    }
+
+    pub(crate) fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Map this spec's `param_type` onto an OpenAPI/JSON Schema `type`.
+    pub(crate) fn openapi_type(&self) -> &'static str {
+        match self.param_type.as_str() {
+            "int" => "integer",
+            "float" => "number",
+            "bool" => "boolean",
+            _ => "string",
+        }
+    }
+}
+
+/// A single parameter that failed coercion or validation, reported back to
+/// the client as part of a structured 400 response.
+pub struct ValidationError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Coerce and validate `specs` against the request's path/query parameters
+/// and (optionally) its JSON body, returning the validated params as a JSON
+/// object keyed by parameter name.
+///
+/// Stops at the first failing parameter so the response describes exactly
+/// one field and why it failed, rather than dumping every error at once.
+pub fn validate_params(
+    specs: &[ParamSpec],
+    path_params: &HashMap<String, String>,
+    query_string: Option<&str>,
+    body: Option<&serde_json::Value>,
+) -> Result<serde_json::Map<String, serde_json::Value>, ValidationError> {
+    let query_params: HashMap<String, String> = query_string
+        .map(|q| crate::request::parse_encoded_pairs(q).into_iter().collect())
+        .unwrap_or_default();
+
+    let mut result = serde_json::Map::new();
+
+    for spec in specs {
+        let raw = match spec.location.as_str() {
+            "path" => path_params.get(&spec.name).cloned(),
+            "query" => query_params.get(&spec.name).cloned(),
+            "body" => body.and_then(|b| b.get(&spec.name)).map(|v| match v {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            }),
+            other => {
+                return Err(ValidationError {
+                    field: spec.name.clone(),
+                    message: format!("Unknown parameter location '{}'", other),
+                })
+            }
+        };
+
+        let value = match raw {
+            Some(raw_str) => coerce(&spec.name, &raw_str, &spec.param_type)?,
+            None => match &spec.default_json {
+                Some(default) => default.clone(),
+                None => {
+                    if spec.required {
+                        return Err(ValidationError {
+                            field: spec.name.clone(),
+                            message: "Missing required parameter".to_string(),
+                        });
+                    }
+                    serde_json::Value::Null
+                }
+            },
+        };
+
+        check_bounds(&spec.name, &value, spec.min, spec.max)?;
+        check_regex(&spec.name, &value, spec.regex.as_deref())?;
+
+        result.insert(spec.name.clone(), value);
+    }
+
+    Ok(result)
+}
+
+fn coerce(field: &str, raw: &str, param_type: &str) -> Result<serde_json::Value, ValidationError> {
+    match param_type {
+        "int" => raw
+            .parse::<i64>()
+            .map(|v| serde_json::Value::Number(v.into()))
+            .map_err(|_| ValidationError {
+                field: field.to_string(),
+                message: format!("'{}' is not a valid int", raw),
+            }),
+        "float" => raw
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(serde_json::Value::Number)
+            .ok_or_else(|| ValidationError {
+                field: field.to_string(),
+                message: format!("'{}' is not a valid float", raw),
+            }),
+        "bool" => match raw.to_ascii_lowercase().as_str() {
+            "true" | "1" => Ok(serde_json::Value::Bool(true)),
+            "false" | "0" => Ok(serde_json::Value::Bool(false)),
+            _ => Err(ValidationError {
+                field: field.to_string(),
+                message: format!("'{}' is not a valid bool", raw),
+            }),
+        },
+        "uuid" => {
+            if is_valid_uuid(raw) {
+                Ok(serde_json::Value::String(raw.to_string()))
+            } else {
+                Err(ValidationError {
+                    field: field.to_string(),
+                    message: format!("'{}' is not a valid uuid", raw),
+                })
+            }
+        }
+        "str" => Ok(serde_json::Value::String(raw.to_string())),
+        other => Err(ValidationError {
+            field: field.to_string(),
+            message: format!("Unknown parameter type '{}'", other),
+        }),
+    }
+}
+
+fn is_valid_uuid(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('-').collect();
+    let lens = [8, 4, 4, 4, 12];
+    parts.len() == 5
+        && parts
+            .iter()
+            .zip(lens)
+            .all(|(p, len)| p.len() == len && p.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn check_bounds(
+    field: &str,
+    value: &serde_json::Value,
+    min: Option<f64>,
+    max: Option<f64>,
+) -> Result<(), ValidationError> {
+    if min.is_none() && max.is_none() {
+        return Ok(());
+    }
+    let Some(n) = value.as_f64() else {
+        return Ok(());
+    };
+
+    if let Some(min) = min {
+        if n < min {
+            return Err(ValidationError {
+                field: field.to_string(),
+                message: format!("must be >= {}", min),
+            });
+        }
+    }
+    if let Some(max) = max {
+        if n > max {
+            return Err(ValidationError {
+                field: field.to_string(),
+                message: format!("must be <= {}", max),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_regex(
+    field: &str,
+    value: &serde_json::Value,
+    pattern: Option<&str>,
+) -> Result<(), ValidationError> {
+    let Some(pattern) = pattern else {
+        return Ok(());
+    };
+    let Some(s) = value.as_str() else {
+        return Ok(());
+    };
+
+    let re = regex::Regex::new(pattern).map_err(|e| ValidationError {
+        field: field.to_string(),
+        message: format!("Invalid regex '{}': {}", pattern, e),
+    })?;
+
+    if re.is_match(s) {
+        Ok(())
+    } else {
+        Err(ValidationError {
+            field: field.to_string(),
+            message: format!("does not match pattern '{}'", pattern),
+        })
+    }
+}